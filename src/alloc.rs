@@ -0,0 +1,122 @@
+#[cfg(feature = "alloc-stats")]
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Whether allocations are counted, which is the case when the `alloc-stats` feature is enabled
+pub const ENABLED: bool = cfg!(feature = "alloc-stats");
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+/// Wraps the system allocator and keeps track of how much memory is allocated
+#[cfg(feature = "alloc-stats")]
+pub struct Counting;
+
+#[cfg(feature = "alloc-stats")]
+impl Counting {
+    fn grow(&self, size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+#[cfg(feature = "alloc-stats")]
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        self.shrink(layout.size());
+    }
+
+    /// A reallocation counts as allocating the new size and freeing the old one
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            self.grow(new_size);
+            self.shrink(layout.size());
+        }
+        new_ptr
+    }
+}
+
+/// How much memory was allocated during a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Memory {
+    /// The largest number of bytes that were allocated at the same time, not counting memory that
+    /// was already allocated before the run
+    pub peak: usize,
+
+    /// The number of allocations, including reallocations
+    pub allocations: usize,
+
+    /// The total number of bytes allocated
+    pub allocated: usize,
+}
+
+/// Measure the memory allocated while calling the given function. Returns `None` unless
+/// allocations are counted. Allocations made by other threads at the same time are included
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Memory>) {
+    if !ENABLED {
+        return (f(), None);
+    }
+
+    let baseline = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+
+    let out = f();
+    let memory = Memory {
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(baseline),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        allocated: ALLOCATED.load(Ordering::Relaxed) - allocated,
+    };
+    (out, Some(memory))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        if !ENABLED {
+            assert_eq!(measure(|| vec![0u8; 16]).1, None);
+            return;
+        }
+
+        let (v, memory) = measure(|| vec![0u8; 1 << 20]);
+        let memory = memory.unwrap();
+        assert_eq!(v.len(), 1 << 20);
+        assert!(memory.peak >= 1 << 20);
+        assert!(memory.allocated >= 1 << 20);
+        assert!(memory.allocations >= 1);
+    }
+}
@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The answer to one part of a puzzle. Answers of different kinds compare equal when they are
+/// written the same way
+#[derive(Debug, Clone, Eq)]
+pub enum Answer {
+    Integer(u64),
+    Signed(i64),
+
+    /// A single line of text, like a comma separated list or a coordinate
+    Text(String),
+
+    /// Text spanning several lines, like letters drawn in ASCII art
+    Multiline(String),
+}
+
+impl Answer {
+    /// Interpret an answer written as text, like the ones in the answers file. Numbers are only
+    /// recognized when they are written the way they are displayed, so `007` stays text
+    pub fn parse(s: &str) -> Self {
+        let answer = if let Ok(n) = s.parse() {
+            Self::Integer(n)
+        } else if let Ok(n) = s.parse() {
+            Self::Signed(n)
+        } else {
+            return Self::from(s);
+        };
+        if answer.to_string() == s {
+            answer
+        } else {
+            Self::from(s)
+        }
+    }
+
+    /// Compare two numeric answers. Returns `None` unless both are numbers
+    pub fn cmp_numeric(&self, other: &Self) -> Option<Ordering> {
+        let value = |answer: &Self| match *answer {
+            Self::Integer(n) => Some(i128::from(n)),
+            Self::Signed(n) => Some(i128::from(n)),
+            _ => None,
+        };
+        Some(value(self)?.cmp(&value(other)?))
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Integer(a), Self::Integer(b)) => a == b,
+            (Self::Signed(a), Self::Signed(b)) => a == b,
+            (Self::Text(a), Self::Text(b)) | (Self::Multiline(a), Self::Multiline(b)) => a == b,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(n) => write!(f, "{}", n),
+            Self::Signed(n) => write!(f, "{}", n),
+            Self::Text(s) | Self::Multiline(s) => f.write_str(s),
+        }
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident: $target:ty => $($source:ty),*) => {
+        $(
+            impl From<$source> for Answer {
+                fn from(n: $source) -> Self {
+                    // All supported platforms have pointers of at most 64 bits
+                    Self::$variant(n as $target)
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(Integer: u64 => u8, u16, u32, u64, usize);
+answer_from!(Signed: i64 => i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        if s.contains('\n') {
+            Self::Multiline(s)
+        } else {
+            Self::Text(s)
+        }
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Self::from(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from() {
+        assert_eq!(Answer::from(12usize), Answer::Integer(12));
+        assert_eq!(Answer::from(-3isize), Answer::Signed(-3));
+        assert_eq!(Answer::from("1,2,3"), Answer::Text("1,2,3".to_string()));
+        assert_eq!(
+            Answer::from("#.\n.#"),
+            Answer::Multiline("#.\n.#".to_string())
+        );
+    }
+
+    #[test]
+    fn test_eq() {
+        assert_eq!(Answer::Text("5".to_string()), Answer::Integer(5));
+        assert_eq!(Answer::Signed(5), Answer::Integer(5));
+        assert_eq!(Answer::Text("-5".to_string()), Answer::Signed(-5));
+        assert_ne!(Answer::Text("05".to_string()), Answer::Integer(5));
+        assert_ne!(Answer::from("a"), Answer::from("b"));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Answer::parse("1289579105366"),
+            Answer::from(1289579105366usize)
+        );
+        assert_eq!(Answer::parse("-7"), Answer::from(-7i32));
+        assert_eq!(Answer::parse("6,5"), Answer::from("6,5"));
+        assert_eq!(Answer::parse("42"), Answer::from(42i64));
+        assert_ne!(Answer::parse("-1"), Answer::from(u64::MAX));
+        assert_eq!(
+            Answer::parse("-1").cmp_numeric(&Answer::from(u64::MAX)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(Answer::parse("1").cmp_numeric(&Answer::from("a")), None);
+        assert_eq!(Answer::parse("007"), Answer::from("007"));
+        assert_ne!(Answer::parse("007"), Answer::from(7usize));
+        for s in ["42", "-1", "007", "+5", "abc", "a\nb"] {
+            assert_eq!(Answer::parse(s).to_string(), s);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Context as _, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::answer::Answer;
+use crate::input::read_optional;
+
+/// The default location of the expected answers
+pub const DEFAULT_PATH: &str = "data/answers.txt";
+
+/// Known correct answers for the real puzzle inputs. The file format is one
+/// `<day> <a|b> <answer>` entry per line. Blank lines and lines starting with `#` are ignored
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers(BTreeMap<(usize, char), String>);
+
+/// Whether an answer matches the expected answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+}
+
+impl Verdict {
+    /// Compare an answer to the expected answer. Returns `None` if either is unknown
+    pub fn of(expected: Option<&Answer>, actual: Option<&Answer>) -> Option<Self> {
+        match (expected?, actual?) {
+            (expected, actual) if expected == actual => Some(Self::Correct),
+            _ => Some(Self::Wrong),
+        }
+    }
+
+    pub fn mark(&self) -> &'static str {
+        match self {
+            Self::Correct => "✓",
+            Self::Wrong => "✗",
+        }
+    }
+}
+
+impl Answers {
+    /// Load answers from the given file. A missing file is the same as an empty one
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let Some(contents) =
+            read_optional(path).with_context(|| format!("Failed to open answers file {path:?}"))?
+        else {
+            return Ok(Self::default());
+        };
+        Self::parse(&contents).with_context(|| format!("Failed to parse answers file {path:?}"))
+    }
+
+    fn parse(contents: &str) -> Result<Self> {
+        let mut answers = Self::default();
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.splitn(3, ' ');
+            let (Some(day), Some(part), Some(answer)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(anyhow!("Expected <day> <part> <answer> on line {}", i + 1));
+            };
+            let day = day
+                .parse()
+                .with_context(|| format!("Invalid day on line {}", i + 1))?;
+            let part = match part {
+                "a" | "A" => 'a',
+                "b" | "B" => 'b',
+                _ => return Err(anyhow!("Invalid part {:?} on line {}", part, i + 1)),
+            };
+            answers.0.insert((day, part), answer.to_string());
+        }
+        Ok(answers)
+    }
+
+    pub fn a(&self, day: usize) -> Option<&str> {
+        self.0.get(&(day, 'a')).map(String::as_str)
+    }
+
+    pub fn b(&self, day: usize) -> Option<&str> {
+        self.0.get(&(day, 'b')).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse("# comment\n\n7 a 3749\n7 B 11387\n8 a two words\n").unwrap();
+        assert_eq!(answers.a(7), Some("3749"));
+        assert_eq!(answers.b(7), Some("11387"));
+        assert_eq!(answers.a(8), Some("two words"));
+        assert_eq!(answers.b(8), None);
+        assert!(Answers::parse("7 c 1").is_err());
+        assert!(Answers::parse("7 a").is_err());
+    }
+
+    #[test]
+    fn test_verdict() {
+        let (one, two) = (Answer::from(1usize), Answer::from(2usize));
+        assert_eq!(Verdict::of(Some(&one), Some(&one)), Some(Verdict::Correct));
+        assert_eq!(Verdict::of(Some(&one), Some(&two)), Some(Verdict::Wrong));
+        assert_eq!(Verdict::of(None, Some(&two)), None);
+        assert_eq!(Verdict::of(Some(&one), None), None);
+    }
+}
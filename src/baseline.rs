@@ -0,0 +1,216 @@
+use anyhow::{anyhow, Context as _, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::input::read_optional;
+use crate::report::format_duration;
+
+/// Check a baseline label given on the command line. Labels are stored as a single field in the
+/// baseline file, so they can't be empty or contain whitespace
+pub fn parse_label(s: &str) -> Result<String> {
+    if s.is_empty() {
+        return Err(anyhow!("The label can't be empty"));
+    }
+    if s.contains(char::is_whitespace) {
+        return Err(anyhow!(
+            "Invalid label {:?}, labels can't contain whitespace",
+            s
+        ));
+    }
+    Ok(s.to_string())
+}
+
+/// Parse the regression threshold given on the command line, which is a percentage of at least 0
+pub fn parse_threshold(s: &str) -> Result<f64> {
+    let threshold: f64 = s
+        .parse()
+        .with_context(|| format!("Invalid threshold {:?}", s))?;
+    if !threshold.is_finite() || threshold < 0.0 {
+        return Err(anyhow!(
+            "Invalid threshold {:?}, expected a percentage of at least 0",
+            s
+        ));
+    }
+    Ok(threshold)
+}
+
+/// Benchmark medians keyed by a user chosen label and day. The file format is one
+/// `<label> <day> <median ns>` entry per line
+#[derive(Debug, Default, PartialEq)]
+pub struct Baselines(BTreeMap<(String, usize), Duration>);
+
+impl Baselines {
+    /// Load baselines from the given file. A missing file is the same as an empty one
+    pub fn load(path: &Path) -> Result<Self> {
+        let Some(contents) = read_optional(path)
+            .with_context(|| format!("Failed to open baseline file {path:?}"))?
+        else {
+            return Ok(Self::default());
+        };
+        Self::parse(&contents).with_context(|| format!("Failed to parse baseline file {path:?}"))
+    }
+
+    fn parse(contents: &str) -> Result<Self> {
+        let mut baselines = Self::default();
+        for (i, line) in contents.lines().enumerate() {
+            let fields: Vec<_> = line.split_whitespace().collect();
+            let [label, day, ns] = fields[..] else {
+                return Err(anyhow!("Expected three fields on line {}", i + 1));
+            };
+            let day = day
+                .parse()
+                .with_context(|| format!("Invalid day on line {}", i + 1))?;
+            let ns = ns
+                .parse()
+                .with_context(|| format!("Invalid duration on line {}", i + 1))?;
+            baselines.insert(label, day, Duration::from_nanos(ns));
+        }
+        Ok(baselines)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory {dir:?}"))?;
+        }
+        fs::write(path, self.to_string())
+            .with_context(|| format!("Failed to write baseline file {path:?}"))
+    }
+
+    pub fn get(&self, label: &str, day: usize) -> Option<Duration> {
+        self.0.get(&(label.to_string(), day)).copied()
+    }
+
+    pub fn insert(&mut self, label: &str, day: usize, median: Duration) {
+        self.0.insert((label.to_string(), day), median);
+    }
+
+    pub fn has_label(&self, label: &str) -> bool {
+        self.0.keys().any(|(l, _)| l == label)
+    }
+}
+
+impl std::fmt::Display for Baselines {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for ((label, day), median) in self.0.iter() {
+            writeln!(f, "{} {} {}", label, day, median.as_nanos())?;
+        }
+        Ok(())
+    }
+}
+
+/// A benchmark result compared to its baseline
+#[derive(Debug, Clone, Copy)]
+pub struct Comparison {
+    pub day: usize,
+    pub baseline: Option<Duration>,
+    pub current: Duration,
+}
+
+impl Comparison {
+    /// The relative change in percent, where a positive value means slower. A baseline of zero is
+    /// treated as missing, since there is nothing to compare to
+    pub fn change(&self) -> Option<f64> {
+        let baseline = self.baseline.filter(|b| !b.is_zero())?.as_secs_f64();
+        Some((self.current.as_secs_f64() - baseline) / baseline * 100.0)
+    }
+
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.change().is_some_and(|change| change > threshold)
+    }
+}
+
+/// Print a table comparing each day to its baseline
+pub fn print_comparisons(label: &str, comparisons: &[Comparison], threshold: f64) {
+    println!("Compared to baseline {label:?} (regression threshold {threshold}%)");
+    println!();
+    println!(
+        "{:>3}  {:>10}  {:>10}  {:>8}",
+        "Day", "Baseline", "Current", "Change"
+    );
+    for cmp in comparisons {
+        let baseline = cmp.baseline.map(format_duration).unwrap_or_default();
+        let change = cmp
+            .change()
+            .map(|change| format!("{change:+.1}%"))
+            .unwrap_or_else(|| "new".to_string());
+        let flag = if cmp.is_regression(threshold) {
+            "  REGRESSION"
+        } else {
+            ""
+        };
+        println!(
+            "{:>3}  {:>10}  {:>10}  {:>8}{}",
+            cmp.day,
+            baseline,
+            format_duration(cmp.current),
+            change,
+            flag
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut baselines = Baselines::default();
+        baselines.insert("before", 7, Duration::from_millis(900));
+        baselines.insert("before", 1, Duration::from_micros(250));
+        baselines.insert("after", 7, Duration::from_millis(12));
+
+        let serialized = baselines.to_string();
+        assert_eq!(
+            serialized,
+            "after 7 12000000\nbefore 1 250000\nbefore 7 900000000\n"
+        );
+        assert_eq!(Baselines::parse(&serialized).unwrap(), baselines);
+        assert!(Baselines::parse("before 7").is_err());
+    }
+
+    #[test]
+    fn test_parse_label() {
+        assert_eq!(parse_label("before-fix").unwrap(), "before-fix");
+        assert!(parse_label("").is_err());
+        assert!(parse_label("before fix").is_err());
+        assert!(parse_label("before\tfix").is_err());
+    }
+
+    #[test]
+    fn test_parse_threshold() {
+        assert_eq!(parse_threshold("10").unwrap(), 10.0);
+        assert_eq!(parse_threshold("0").unwrap(), 0.0);
+        for s in ["-5", "nan", "inf", "abc", ""] {
+            assert!(parse_threshold(s).is_err(), "{:?} was accepted", s);
+        }
+    }
+
+    #[test]
+    fn test_regression() {
+        let cmp = Comparison {
+            day: 7,
+            baseline: Some(Duration::from_millis(100)),
+            current: Duration::from_millis(115),
+        };
+        assert!((cmp.change().unwrap() - 15.0).abs() < 1e-9);
+        assert!(cmp.is_regression(10.0));
+        assert!(!cmp.is_regression(20.0));
+
+        let new = Comparison {
+            baseline: None,
+            ..cmp
+        };
+        assert!(!new.is_regression(0.0));
+
+        let zero = Comparison {
+            baseline: Some(Duration::ZERO),
+            ..cmp
+        };
+        assert_eq!(zero.change(), None);
+        assert!(!zero.is_regression(0.0));
+    }
+}
@@ -0,0 +1,209 @@
+use anyhow::{anyhow, Context as _, Result};
+use std::time::{Duration, Instant};
+
+use crate::cancel;
+
+/// Limits for how long a solution is benchmarked
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    /// Total time to spend on measured runs
+    pub time: Duration,
+
+    /// Maximum number of measured runs
+    pub iterations: usize,
+}
+
+/// Parse a number of seconds given on the command line, which may be fractional
+pub fn parse_seconds(s: &str) -> Result<Duration> {
+    let seconds: f64 = s
+        .parse()
+        .with_context(|| format!("Invalid number of seconds {:?}", s))?;
+    Duration::try_from_secs_f64(seconds).map_err(|_| {
+        anyhow!(
+            "Invalid number of seconds {:?}, expected a number of at least 0",
+            s
+        )
+    })
+}
+
+/// Summary statistics of a benchmark
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub std_dev: Duration,
+    pub p95: Duration,
+}
+
+impl Stats {
+    /// Compute statistics for the given samples. Returns `None` if there are no samples
+    pub fn from_samples(mut samples: Vec<Duration>) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort();
+
+        let n = samples.len();
+        let median = if n % 2 == 0 {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        let mean = samples.iter().map(|s| s.as_secs_f64()).sum::<f64>() / n as f64;
+        let variance = samples
+            .iter()
+            .map(|s| (s.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n as f64;
+
+        // Use the nearest rank method, which always picks one of the actual samples
+        let p95_rank = (n * 95).div_ceil(100);
+
+        Some(Self {
+            runs: n,
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+            p95: samples[p95_rank - 1],
+        })
+    }
+}
+
+/// The time spent in each phase of a solution
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Phases {
+    pub parse: Duration,
+    pub part_a: Duration,
+    pub part_b: Duration,
+}
+
+impl Phases {
+    /// The median of each phase individually. Returns `None` if there are no samples
+    pub fn median(samples: &[Phases]) -> Option<Self> {
+        let median = |phase: fn(&Phases) -> Duration| {
+            Stats::from_samples(samples.iter().map(phase).collect()).map(|stats| stats.median)
+        };
+        Some(Self {
+            parse: median(|p| p.parse)?,
+            part_a: median(|p| p.part_a)?,
+            part_b: median(|p| p.part_b)?,
+        })
+    }
+}
+
+/// How long a solution took to run
+#[derive(Debug, Clone, Copy)]
+pub enum Timing {
+    Once(Duration),
+    Bench(Stats),
+}
+
+impl Timing {
+    /// The most representative duration, which is the median when benchmarking
+    pub fn typical(&self) -> Duration {
+        match self {
+            Self::Once(time) => *time,
+            Self::Bench(stats) => stats.median,
+        }
+    }
+}
+
+/// Time a single call of the given function
+pub fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let out = f();
+    (out, Instant::now().saturating_duration_since(start))
+}
+
+/// Run the given function repeatedly until the budget is spent. The function is warmed up for a
+/// tenth of the budget (but at least once) before any measurements are taken. The outputs of the
+/// measured runs are returned in order together with their statistics, so there is always at least
+/// one. Stops between runs if the current thread is cancelled
+pub fn bench<T>(mut f: impl FnMut() -> Result<T>, budget: Budget) -> Result<(Vec<T>, Stats)> {
+    let token = cancel::current();
+    let warmup_start = Instant::now();
+    f()?;
+    let mut warmup_runs = 1;
+    while warmup_runs < budget.iterations / 10 && warmup_start.elapsed() < budget.time / 10 {
+        token.check()?;
+        f()?;
+        warmup_runs += 1;
+    }
+
+    let mut outputs = Vec::new();
+    let mut samples = Vec::new();
+    let start = Instant::now();
+    while samples.is_empty() || (samples.len() < budget.iterations && start.elapsed() < budget.time)
+    {
+        token.check()?;
+        let (result, elapsed) = time(&mut f);
+        outputs.push(result?);
+        samples.push(elapsed);
+    }
+
+    let stats = Stats::from_samples(samples).expect("There is always at least one sample");
+    Ok((outputs, stats))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ms(v: u64) -> Duration {
+        Duration::from_millis(v)
+    }
+
+    #[test]
+    fn test_parse_seconds() {
+        assert_eq!(parse_seconds("3").unwrap(), ms(3000));
+        assert_eq!(parse_seconds("0.25").unwrap(), ms(250));
+        assert_eq!(parse_seconds("0").unwrap(), Duration::ZERO);
+        for s in ["-1", "nan", "inf", "1e400", "abc", ""] {
+            assert!(parse_seconds(s).is_err(), "{:?} was accepted", s);
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(vec![ms(4), ms(2), ms(8), ms(6)]).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, ms(2));
+        assert_eq!(stats.median, ms(5));
+        assert_eq!(stats.mean, ms(5));
+        assert_eq!(stats.p95, ms(8));
+        let expected_std_dev = Duration::from_secs_f64(5f64.sqrt() / 1000.0);
+        assert!(stats.std_dev.abs_diff(expected_std_dev) < Duration::from_micros(1));
+    }
+
+    #[test]
+    fn test_stats_p95() {
+        let stats = Stats::from_samples((1..=100).rev().map(ms).collect()).unwrap();
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.p95, ms(95));
+        assert_eq!(Stats::from_samples(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_bench_respects_iterations() {
+        let mut calls = 0;
+        let budget = Budget {
+            time: Duration::from_secs(60),
+            iterations: 5,
+        };
+        let (outputs, stats) = bench(
+            || {
+                calls += 1;
+                Ok(calls)
+            },
+            budget,
+        )
+        .unwrap();
+        // The first call is the warmup, which isn't measured
+        assert_eq!(outputs, [2, 3, 4, 5, 6]);
+        assert_eq!(stats.runs, 5);
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::report::format_duration;
+
+/// The error returned by solutions that stop early because they were cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// The error for a day that didn't finish before the deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut(pub Duration);
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Timed out after {}", format_duration(self.0))
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// A flag that asks a running solution to stop. Cancellation is cooperative, so it only has an
+/// effect on solutions that check the token while working
+#[derive(Debug, Clone, Default)]
+pub struct Token(Arc<AtomicBool>);
+
+impl Token {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Return an error if the token is cancelled, which makes it easy to bail out of loops
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Token> = RefCell::default();
+}
+
+/// Return the token of the current thread, which is never cancelled unless the thread runs with a
+/// token from `with_token`. Threads don't inherit the token, so solutions using rayon must get it
+/// before starting parallel work
+pub fn current() -> Token {
+    CURRENT.with(|token| token.borrow().clone())
+}
+
+/// Run the function with the token as the token of the current thread
+pub fn with_token<T>(token: Token, f: impl FnOnce() -> T) -> T {
+    let previous = CURRENT.with(|current| current.replace(token));
+    let out = f();
+    CURRENT.with(|current| current.replace(previous));
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_token() {
+        let token = Token::default();
+        assert!(current().check().is_ok());
+
+        let is_cancelled = with_token(token.clone(), || {
+            token.cancel();
+            current().is_cancelled()
+        });
+        assert!(is_cancelled);
+        assert!(!current().is_cancelled());
+
+        let err = token.check().unwrap_err();
+        assert_eq!(err.downcast_ref::<Cancelled>(), Some(&Cancelled));
+    }
+}
@@ -0,0 +1,311 @@
+use anyhow::{anyhow, Context as _, Result};
+use std::env;
+use std::fs;
+use std::io::{ErrorKind, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// The year of the puzzles, which is part of every URL
+const YEAR: usize = 2024;
+
+/// The site to talk to unless another one is given
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// The environment variable with the session token. Takes precedence over the session file
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// The file with the session token, which is the value of the `session` cookie on the site
+pub const DEFAULT_SESSION_FILE: &str = ".aoc-session";
+
+/// The file whose modification time is the time of the last request
+pub const DEFAULT_STAMP_FILE: &str = "target/aoc-last-request";
+
+/// Identify the tool to the site operators, as they ask automated tools to do
+const USER_AGENT: &str = "github.com/runfalk/advent-of-code-2024 by andreas@runfalk.se";
+
+/// A way of sending HTTP requests, so the client can be tested without a network
+pub trait Http {
+    /// Send a GET request with the session cookie and return the body of a successful response
+    fn get(&self, url: &str, session: &str) -> Result<String>;
+
+    /// Send a POST request with the session cookie and the given form fields and return the body
+    /// of a successful response
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<String>;
+}
+
+/// Sends requests using the `curl` command line tool
+pub struct Curl;
+
+impl Curl {
+    fn request(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<String> {
+        // The cookie is given through stdin so the token doesn't show up in the process list. The
+        // token is checked when it's read, so it can't break out of the quoted header
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--config", "-"])
+            .args(["--user-agent", USER_AGENT])
+            .args(form.iter().flat_map(|(key, value)| {
+                ["--data-urlencode".to_string(), format!("{}={}", key, value)]
+            }))
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run curl")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "header = \"Cookie: session={}\"", session)
+                .context("Failed to pass the session to curl")?;
+        }
+
+        let output = child.wait_with_output().context("Failed to run curl")?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Request to {} failed: {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        String::from_utf8(output.stdout).with_context(|| format!("Invalid response from {}", url))
+    }
+}
+
+impl Http for Curl {
+    fn get(&self, url: &str, session: &str) -> Result<String> {
+        self.request(url, session, &[])
+    }
+
+    /// Curl sends a POST request whenever there is form data
+    fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<String> {
+        self.request(url, session, form)
+    }
+}
+
+/// Read the session token from the environment, or from the given file if the variable isn't set
+pub fn session(file: &Path) -> Result<String> {
+    if let Some(token) = env::var(SESSION_VAR).ok().filter(|t| !t.trim().is_empty()) {
+        return check_session(token.trim()).with_context(|| format!("Invalid {}", SESSION_VAR));
+    }
+    match fs::read_to_string(file) {
+        Ok(token) if !token.trim().is_empty() => {
+            check_session(token.trim()).with_context(|| format!("Invalid session file {:?}", file))
+        }
+        Ok(_) => Err(anyhow!("Session file {:?} is empty", file)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Err(anyhow!(
+            "No session token. Set {} or write it to {:?}",
+            SESSION_VAR,
+            file
+        )),
+        Err(e) => Err(e).with_context(|| format!("Failed to read session file {:?}", file)),
+    }
+}
+
+/// Check that the session token only has letters and digits, like the tokens the site hands out.
+/// The token is written into the curl config, where anything else could add other options
+fn check_session(token: &str) -> Result<String> {
+    if !token.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(anyhow!(
+            "The session token may only contain letters and digits"
+        ));
+    }
+    Ok(token.to_string())
+}
+
+/// Keeps requests at least an interval apart, even across runs, by remembering the time of the last
+/// request in a file
+#[derive(Debug, Clone)]
+pub struct Throttle {
+    pub stamp: PathBuf,
+    pub interval: Duration,
+}
+
+impl Throttle {
+    /// Sleep until the interval since the last request has passed and record a new request
+    pub fn wait(&self) -> Result<()> {
+        let last = fs::metadata(&self.stamp).and_then(|m| m.modified()).ok();
+        if let Some(delay) = delay(last, SystemTime::now(), self.interval) {
+            eprintln!(
+                "Waiting {:.1}s before the next request",
+                delay.as_secs_f64()
+            );
+            thread::sleep(delay);
+        }
+
+        if let Some(dir) = self.stamp.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory {dir:?}"))?;
+        }
+        fs::write(&self.stamp, "")
+            .with_context(|| format!("Failed to write request time to {:?}", self.stamp))
+    }
+}
+
+/// Return how long to wait before the next request, if at all
+fn delay(last: Option<SystemTime>, now: SystemTime, interval: Duration) -> Option<Duration> {
+    // A last request in the future means the clock changed, in which case waiting is pointless
+    let elapsed = now.duration_since(last?).ok()?;
+    interval.checked_sub(elapsed).filter(|d| !d.is_zero())
+}
+
+/// Talks to the Advent of Code site
+pub struct Client<H> {
+    pub http: H,
+    pub base_url: String,
+    pub session: String,
+    pub throttle: Throttle,
+}
+
+impl<H: Http> Client<H> {
+    fn url(&self, day: usize, path: &str) -> String {
+        let base_url = self.base_url.trim_end_matches('/');
+        format!("{}/{}/day/{}{}", base_url, YEAR, day, path)
+    }
+
+    /// Download the puzzle input for the given day
+    pub fn input(&self, day: usize) -> Result<String> {
+        self.throttle.wait()?;
+        let input = self.http.get(&self.url(day, "/input"), &self.session)?;
+        if input.trim().is_empty() {
+            return Err(anyhow!("The input for day {} is empty", day));
+        }
+        Ok(input)
+    }
+
+    /// Submit an answer for the given part and return the page the site responds with
+    pub fn submit(&self, day: usize, part: char, answer: &str) -> Result<String> {
+        let level = match part {
+            'a' => "1",
+            'b' => "2",
+            _ => return Err(anyhow!("Invalid part {:?}", part)),
+        };
+        self.throttle.wait()?;
+        self.http.post(
+            &self.url(day, "/answer"),
+            &self.session,
+            &[("level", level), ("answer", answer)],
+        )
+    }
+}
+
+/// Store the input for a day at the given path unless it's already there. An empty file, like the
+/// placeholder for a new day, doesn't count. Returns true if the input was downloaded
+pub fn fetch(path: &Path, download: impl FnOnce() -> Result<String>) -> Result<bool> {
+    match fs::read_to_string(path) {
+        Ok(input) if !input.trim().is_empty() => return Ok(false),
+        Ok(_) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {:?}", path)),
+    }
+
+    let input = download()?;
+    fs::write(path, input).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Answers every request with the same body and remembers the requests
+    struct Mock {
+        body: String,
+        requests: RefCell<Vec<String>>,
+    }
+
+    impl Http for Mock {
+        fn get(&self, url: &str, session: &str) -> Result<String> {
+            assert_eq!(session, "secret");
+            self.requests.borrow_mut().push(url.to_string());
+            Ok(self.body.clone())
+        }
+
+        fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<String> {
+            let fields: Vec<_> = form.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            self.get(&format!("{} {}", url, fields.join("&")), session)
+        }
+    }
+
+    fn client(body: &str, name: &str) -> Client<Mock> {
+        Client {
+            http: Mock {
+                body: body.to_string(),
+                requests: RefCell::new(Vec::new()),
+            },
+            base_url: "http://localhost:8080/".to_string(),
+            session: "secret".to_string(),
+            throttle: Throttle {
+                stamp: env::temp_dir().join(format!("aoc-test-{}-{}", name, std::process::id())),
+                interval: Duration::ZERO,
+            },
+        }
+    }
+
+    #[test]
+    fn test_input() {
+        let client = client("1 2\n3 4\n", "input");
+        assert_eq!(client.input(5).unwrap(), "1 2\n3 4\n");
+        assert_eq!(
+            *client.http.requests.borrow(),
+            ["http://localhost:8080/2024/day/5/input"]
+        );
+        assert!(client.throttle.stamp.exists());
+        fs::remove_file(&client.throttle.stamp).unwrap();
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let client = client("\n", "empty");
+        assert!(client.input(5).is_err());
+        fs::remove_file(&client.throttle.stamp).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let client = client("<article>That's the right answer!</article>", "submit");
+        client.submit(5, 'b', "123").unwrap();
+        assert_eq!(
+            *client.http.requests.borrow(),
+            ["http://localhost:8080/2024/day/5/answer level=2&answer=123"]
+        );
+        assert!(client.submit(5, 'c', "123").is_err());
+        fs::remove_file(&client.throttle.stamp).unwrap();
+    }
+
+    #[test]
+    fn test_fetch() {
+        let path = env::temp_dir().join(format!("aoc-test-fetch-{}.txt", std::process::id()));
+        fs::write(&path, "").unwrap();
+        assert!(fetch(&path, || Ok("42\n".to_string())).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "42\n");
+
+        // An existing input is never downloaded again
+        assert!(!fetch(&path, || panic!("Downloaded the input twice")).unwrap());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_check_session() {
+        assert_eq!(
+            check_session("53616c7465645f5f").unwrap(),
+            "53616c7465645f5f"
+        );
+        for token in ["abc\"def", "abc\\", "abc\noutput = \"x\"", "a b"] {
+            assert!(check_session(token).is_err(), "{:?} was accepted", token);
+        }
+    }
+
+    #[test]
+    fn test_delay() {
+        let now = SystemTime::now();
+        let interval = Duration::from_secs(5);
+        assert_eq!(delay(None, now, interval), None);
+        assert_eq!(
+            delay(Some(now - Duration::from_secs(2)), now, interval),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(delay(Some(now - interval), now, interval), None);
+        assert_eq!(delay(Some(now + interval), now, interval), None);
+    }
+}
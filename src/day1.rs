@@ -1,71 +1,80 @@
-use anyhow::{anyhow, Context as _, Result};
+use anyhow::Result;
 use std::collections::HashMap;
 
-fn part_a(mut first: Vec<usize>, mut second: Vec<usize>) -> usize {
-    first.sort();
-    second.sort();
-    first
-        .into_iter()
-        .zip(second)
-        .map(|(a, b)| a.abs_diff(b))
-        .sum()
-}
+use crate::solution::Solution;
+use crate::utils::parse;
 
-fn part_b(first: Vec<usize>, second: Vec<usize>) -> usize {
-    let mut lookup: HashMap<usize, usize> = HashMap::new();
-    for v in second {
-        *lookup.entry(v).or_insert(0) += 1;
-    }
-    first
-        .into_iter()
-        .map(|v| v * lookup.get(&v).copied().unwrap_or(0))
-        .sum()
+pub struct Lists {
+    first: Vec<usize>,
+    second: Vec<usize>,
 }
 
-fn parse_line(line: &str) -> Result<(usize, usize)> {
-    let mut pair = line.split_whitespace();
-    let a = pair.next().ok_or_else(|| anyhow!("No list found"))?;
-    let b = pair.next().ok_or_else(|| anyhow!("No second list found"))?;
-    if let Some(c) = pair.next() {
-        return Err(anyhow!("Found more than two lists ({:?})", c));
-    }
+pub struct Day1;
 
-    Ok((a.parse()?, b.parse()?))
-}
+impl Solution for Day1 {
+    const DAY: usize = 1;
+    const TITLE: &'static str = "Historian Hysteria";
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let mut first = Vec::new();
-    let mut second = Vec::new();
+    type Input = Lists;
+    type A = usize;
+    type B = usize;
 
-    for (i, line) in input.lines().enumerate() {
-        let (a, b) = parse_line(line).with_context(|| format!("Failed to parse line {}", i + 1))?;
-        first.push(a);
-        second.push(b);
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (first, second) = parse::lines(input, |line| parse::pair::<usize, usize>(line, " "))?
+            .into_iter()
+            .unzip();
+        Ok(Lists { first, second })
     }
 
-    Ok((
-        part_a(first.clone(), second.clone()),
-        Some(part_b(first, second)),
-    ))
+    fn part_a(lists: &Self::Input) -> Result<Self::A> {
+        let mut first = lists.first.clone();
+        let mut second = lists.second.clone();
+        first.sort();
+        second.sort();
+        Ok(first
+            .into_iter()
+            .zip(second)
+            .map(|(a, b)| a.abs_diff(b))
+            .sum())
+    }
+
+    fn part_b(lists: &Self::Input) -> Result<Option<Self::B>> {
+        let mut lookup: HashMap<usize, usize> = HashMap::new();
+        for v in lists.second.iter().copied() {
+            *lookup.entry(v).or_insert(0) += 1;
+        }
+        Ok(Some(lists
+            .first
+            .iter()
+            .map(|v| v * lookup.get(v).copied().unwrap_or(0))
+            .sum()))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    test_real_input!(1, 1341714, 27384707);
+    test_real_input!(1);
 
     const FIRST_LIST: &[usize] = &[3, 4, 2, 1, 3, 3];
 
     const SECOND_LIST: &[usize] = &[4, 3, 5, 3, 9, 3];
 
+    fn example() -> Lists {
+        Lists {
+            first: FIRST_LIST.to_vec(),
+            second: SECOND_LIST.to_vec(),
+        }
+    }
+
     #[test]
     fn test_part_a() {
-        assert_eq!(part_a(FIRST_LIST.to_vec(), SECOND_LIST.to_vec()), 11);
+        assert_eq!(Day1::part_a(&example()).unwrap(), 11);
     }
 
     #[test]
     fn test_part_b() {
-        assert_eq!(part_b(FIRST_LIST.to_vec(), SECOND_LIST.to_vec()), 31);
+        assert_eq!(Day1::part_b(&example()).unwrap(), Some(31));
     }
 }
@@ -1,59 +1,74 @@
-use anyhow::{anyhow, Result};
-use std::collections::{HashMap, HashSet};
-
-fn score_trailheads(
-    height_map: &HashMap<u32, HashSet<(isize, isize)>>,
-    overlapping_paths: bool,
-) -> usize {
-    let mut trailhead_score = 0;
-    for start in height_map.get(&0).iter().flat_map(|p| p.iter().copied()) {
-        let mut to_visit = vec![(0u32, start)];
-
-        let mut visited = HashSet::new();
-        visited.insert(start);
-
-        while let Some((elevation, (x, y))) = to_visit.pop() {
-            if elevation == 9 {
-                trailhead_score += 1;
-                continue;
-            }
-
-            let next_elevation = elevation + 1;
-            let Some(neighbors) = height_map.get(&next_elevation) else {
-                continue;
-            };
-            for n in [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)] {
-                if neighbors.contains(&n) && (overlapping_paths || visited.insert(n)) {
-                    to_visit.push((next_elevation, n));
-                }
-            }
-        }
-    }
-    trailhead_score
+use anyhow::Result;
+
+use crate::solution::Solution;
+use crate::utils::grid::Grid;
+use crate::utils::parse;
+use crate::utils::point::Point;
+use crate::utils::search::{bfs, count_paths};
+
+/// Iterate over the neighbors that are exactly one step higher
+fn uphill(height_map: &Grid<u32>, p: Point) -> impl Iterator<Item = Point> + '_ {
+    height_map
+        .neighbors4(p)
+        .filter(move |&n| height_map[n] == height_map[p] + 1)
 }
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let mut height_map: HashMap<u32, HashSet<(isize, isize)>> = HashMap::new();
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            let height = c
-                .to_digit(10)
-                .ok_or_else(|| anyhow!("Invalid height map {}, at {}x{}", c, x, y))?;
-            height_map
-                .entry(height)
-                .or_default()
-                .insert((x as isize, y as isize));
-        }
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: usize = 10;
+    const TITLE: &'static str = "Hoof It";
+
+    type Input = Grid<u32>;
+    type A = usize;
+    type B = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse::digit_grid(input)
     }
 
-    Ok((
-        score_trailheads(&height_map, false),
-        Some(score_trailheads(&height_map, true)),
-    ))
+    fn part_a(height_map: &Self::Input) -> Result<Self::A> {
+        // The score of a trailhead is the number of peaks it can reach
+        Ok(height_map
+            .positions(&0)
+            .map(|start| {
+                bfs(start, |&p| uphill(height_map, p))
+                    .iter()
+                    .filter(|&(&p, _)| height_map[p] == 9)
+                    .count()
+            })
+            .sum())
+    }
+
+    fn part_b(height_map: &Self::Input) -> Result<Option<Self::B>> {
+        // The rating of a trailhead is the number of distinct trails to any peak
+        Ok(Some(height_map
+            .positions(&0)
+            .map(|start| count_paths(start, |&p| uphill(height_map, p), |&p| height_map[p] == 9))
+            .sum()))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    test_real_input!(10, 746, 1541);
+    test_real_input!(10);
+
+    const EXAMPLE: &str = dedent::dedent!(
+        r#"
+        89010123
+        78121874
+        87430965
+        96549874
+        45678903
+        32019012
+        01329801
+        10456732
+        "#
+    );
+
+    test_examples! {
+        Day10,
+        test_example: EXAMPLE => (36, 81),
+    }
 }
@@ -1,42 +1,70 @@
 use std::collections::HashMap;
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 
-fn blink(stones: &[usize], num_blinks: usize) -> usize {
+use crate::solution::Solution;
+use crate::utils::{checked, parse};
+
+/// Add to the number of stones with the given engraving
+fn add_stones(stones: &mut HashMap<usize, usize>, stone: usize, count: usize) -> Result<()> {
+    let total = stones.entry(stone).or_default();
+    *total = checked::add(*total, count)?;
+    Ok(())
+}
+
+fn blink(stones: &[usize], num_blinks: usize) -> Result<usize> {
     let mut a: HashMap<usize, usize> = HashMap::new();
     for stone in stones.iter().copied() {
-        *a.entry(stone).or_default() += 1
+        add_stones(&mut a, stone, 1).with_context(|| format!("Day {}", Day11::DAY))?;
     }
 
-    for _ in 0..num_blinks {
+    for i in 0..num_blinks {
+        let context = || format!("Day {} blink {}", Day11::DAY, i + 1);
         let mut b: HashMap<usize, usize> = HashMap::new();
         for (stone, count) in a.into_iter() {
             if stone == 0 {
-                *b.entry(stone + 1).or_default() += count;
+                add_stones(&mut b, stone + 1, count).with_context(context)?;
             } else if (stone.ilog10() + 1) % 2 == 0 {
                 let stone_str = stone.to_string();
                 let (left, right) = stone_str.split_at(stone_str.len() / 2);
-                *b.entry(left.parse().unwrap()).or_default() += count;
-                *b.entry(right.parse().unwrap()).or_default() += count;
+                add_stones(&mut b, left.parse().unwrap(), count).with_context(context)?;
+                add_stones(&mut b, right.parse().unwrap(), count).with_context(context)?;
             } else {
-                *b.entry(stone * 2024).or_default() += count;
+                let stone = checked::mul(stone, 2024).with_context(context)?;
+                add_stones(&mut b, stone, count).with_context(context)?;
             }
         }
         a = b;
     }
-    a.into_values().sum()
+    a.into_values()
+        .try_fold(0, checked::add)
+        .with_context(|| format!("Day {} total number of stones", Day11::DAY))
 }
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let stones = input
-        .split_whitespace()
-        .map(|stone_str| stone_str.parse())
-        .collect::<Result<Vec<usize>, _>>()?;
-    Ok((blink(&stones, 25), Some(blink(&stones, 75))))
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: usize = 11;
+    const TITLE: &'static str = "Plutonian Pebbles";
+
+    type Input = Vec<usize>;
+    type A = usize;
+    type B = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse::integers(input)
+    }
+
+    fn part_a(stones: &Self::Input) -> Result<Self::A> {
+        blink(stones, 25)
+    }
+
+    fn part_b(stones: &Self::Input) -> Result<Option<Self::B>> {
+        blink(stones, 75).map(Some)
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::*;
-    test_real_input!(11, 216_996, 257_335_372_288_947);
+    test_real_input!(11);
 }
@@ -1,102 +1,88 @@
 use anyhow::Result;
-use std::collections::{BTreeMap, BTreeSet, HashSet};
 
-use itertools::Itertools;
+use crate::solution::Solution;
+use crate::utils::grid::Grid;
+use crate::utils::point::{Direction, Point};
+use crate::utils::union_find::label_components;
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let mut farm = BTreeMap::new();
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            farm.insert((x as isize, y as isize), c);
+pub struct Farm {
+    regions: Grid<usize>,
+    num_regions: usize,
+}
+
+impl Farm {
+    /// Return true if the garden at `q` belongs to the same region as the garden at `p`
+    fn same_region(&self, p: Point, q: Point) -> bool {
+        self.regions.get(q) == Some(&self.regions[p])
+    }
+
+    /// Sum the area of every region multiplied by the sum of the measurement over its gardens
+    fn price(&self, measure: impl Fn(Point) -> usize) -> usize {
+        let mut areas = vec![0; self.num_regions];
+        let mut measurements = vec![0; self.num_regions];
+        for (p, &region) in self.regions.iter() {
+            areas[region] += 1;
+            measurements[region] += measure(p);
         }
+        areas.iter().zip(measurements).map(|(a, m)| a * m).sum()
     }
+}
 
-    let mut cost_a = 0;
-    let mut cost_b = 0;
-    let mut unchecked_gardens: BTreeSet<(isize, isize)> = farm.keys().copied().collect();
-    while let Some((x, y)) = unchecked_gardens.pop_last() {
-        let region = farm.get(&(x, y)).copied().unwrap();
+pub struct Day12;
 
-        let mut gardens_in_region = HashSet::new();
-        let mut to_visit = vec![(x, y)];
-        while let Some((x, y)) = to_visit.pop() {
-            gardens_in_region.insert((x, y));
-            for neighbor in [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)] {
-                let Some(neighbor_region) = farm.get(&neighbor).copied() else {
-                    continue;
-                };
-                if neighbor_region != region {
-                    continue;
-                }
+impl Solution for Day12 {
+    const DAY: usize = 12;
+    const TITLE: &'static str = "Garden Groups";
 
-                if unchecked_gardens.remove(&neighbor) {
-                    to_visit.push(neighbor);
-                }
-            }
-        }
+    type Input = Farm;
+    type A = usize;
+    type B = usize;
 
-        let area = gardens_in_region.len();
-        let fences = gardens_in_region
-            .iter()
-            .copied()
-            .flat_map(|(x, y)| [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)])
-            .filter(|p| !gardens_in_region.contains(p))
-            .count();
+    /// Split the farm into regions of connected gardens with the same plant
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (regions, num_regions) = label_components(&Grid::parse(input, Ok)?);
+        Ok(Farm {
+            regions,
+            num_regions,
+        })
+    }
 
-        let (min_x, max_x) = gardens_in_region
-            .iter()
-            .copied()
-            .map(|(x, _)| x)
-            .minmax()
-            .into_option()
-            .unwrap();
-        let (min_y, max_y) = gardens_in_region
-            .iter()
-            .copied()
-            .map(|(_, y)| y)
-            .minmax()
-            .into_option()
-            .unwrap();
+    fn part_a(farm: &Self::Input) -> Result<Self::A> {
+        // Every edge to a garden outside the region needs a fence
+        Ok(farm.price(|p| {
+            p.neighbors4()
+                .into_iter()
+                .filter(|&n| !farm.same_region(p, n))
+                .count()
+        }))
+    }
 
-        let mut sides = 0;
-        for (dx, dy) in [(1, 0), (-1, 0)] {
-            for x in min_x..=max_x {
-                let mut is_following_side = false;
-                for y in min_y..=max_y {
-                    let has_neighbor = gardens_in_region.contains(&(x + dx, y + dy));
-                    let is_side = gardens_in_region.contains(&(x, y)) && !has_neighbor;
-                    if !is_following_side && is_side {
-                        sides += 1;
-                    }
-                    is_following_side = is_side;
-                }
-            }
-        }
-        for (dx, dy) in [(0, -1), (0, 1)] {
-            for y in min_y..=max_y {
-                let mut is_following_side = false;
-                for x in min_x..=max_x {
-                    let has_neighbor = gardens_in_region.contains(&(x + dx, y + dy));
-                    let is_side = gardens_in_region.contains(&(x, y)) && !has_neighbor;
-                    if !is_following_side && is_side {
-                        sides += 1;
+    fn part_b(farm: &Self::Input) -> Result<Option<Self::B>> {
+        // A polygon has as many sides as corners, so count the corners each garden contributes
+        Ok(Some(farm.price(|p| {
+            Direction::ORTHOGONAL
+                .into_iter()
+                .filter(|&dir| {
+                    let side = p.step(dir);
+                    let other_side = p.step(dir.turn_right());
+                    let diagonal = side + dir.turn_right().offset();
+                    // An outer corner has both sides outside the region, while an inner corner
+                    // has both sides inside but the diagonal between them outside
+                    match (farm.same_region(p, side), farm.same_region(p, other_side)) {
+                        (false, false) => true,
+                        (true, true) => !farm.same_region(p, diagonal),
+                        _ => false,
                     }
-                    is_following_side = is_side;
-                }
-            }
-        }
-
-        cost_a += fences * area;
-        cost_b += sides * area;
+                })
+                .count()
+        })))
     }
-
-    Ok((cost_a, Some(cost_b)))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    test_real_input!(12, 1_370_258, 805_814);
+    test_real_input!(12);
 
     const EXAMPLE_A: &str = dedent::dedent!(
         r#"
@@ -132,23 +118,11 @@ mod test {
         "#
     );
 
-    #[test]
-    fn test_minimal() {
-        assert_eq!(main("AAB").unwrap(), (16, Some(12)));
-    }
-
-    #[test]
-    fn test_example_a() {
-        assert_eq!(main(EXAMPLE_A).unwrap(), (140, Some(80)));
-    }
-
-    #[test]
-    fn test_example_b() {
-        assert_eq!(main(EXAMPLE_B).unwrap().0, 772);
-    }
-
-    #[test]
-    fn test_example_c() {
-        assert_eq!(main(EXAMPLE_C).unwrap(), (1930, Some(1206)));
+    test_examples! {
+        Day12,
+        test_minimal: "AAB" => (16, 12),
+        test_example_a: EXAMPLE_A => (140, 80),
+        test_example_b: EXAMPLE_B => (772, _),
+        test_example_c: EXAMPLE_C => (1930, 1206),
     }
 }
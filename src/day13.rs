@@ -1,21 +1,18 @@
 use std::sync::OnceLock;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context as _, Result};
 use regex::Regex;
 
-use itertools::Itertools;
+use crate::solution::Solution;
+use crate::utils::{checked, parse};
 
 fn parse_line(s: &str) -> Result<(usize, usize)> {
     static CACHED_REGEX: OnceLock<Regex> = OnceLock::new();
     let re = CACHED_REGEX.get_or_init(|| {
         Regex::new(r"^(?:Button A|Button B|Prize): X[+=](\d+), Y[+=](\d+)$").unwrap()
     });
-    let Some((_, [x, y])) = re.captures(s).map(|caps| caps.extract()) else {
-        return Err(anyhow!("{s:?} is not a valid input line"));
-    };
-
-    // We unwrap here because the regex only matches valid integers
-    Ok((x.parse().unwrap(), y.parse().unwrap()))
+    let [x, y] = parse::captures(re, s)?;
+    Ok((parse::value(x)?, parse::value(y)?))
 }
 
 fn cost_for_prize(
@@ -25,51 +22,176 @@ fn cost_for_prize(
     by: usize,
     prize_x: usize,
     prize_y: usize,
-) -> Option<usize> {
+) -> Result<Option<usize>> {
+    use checked::{add, mul};
+
     // Use Cramer's rule
     // https://en.wikipedia.org/wiki/Cramer%27s_rule#Explicit_formulas_for_small_systems
-    let a = (prize_x * by).abs_diff(bx * prize_y) / (ax * by).abs_diff(ay * bx);
-    let b = (ax * prize_y).abs_diff(prize_x * ay) / (ax * by).abs_diff(bx * ay);
-    if ax * a + bx * b == prize_x && ay * a + by * b == prize_y {
-        Some(3 * a + b)
+    let determinant = mul(ax, by)?.abs_diff(mul(ay, bx)?);
+    if determinant == 0 {
+        // Buttons that don't move the claw can only win a prize that is already under it
+        if (ax, ay, bx, by) == (0, 0, 0, 0) {
+            return Ok(((prize_x, prize_y) == (0, 0)).then_some(0));
+        }
+
+        // The presses aren't unique, which Cramer's rule can't handle. The buttons move along the
+        // same line, so it's enough to look at one axis as long as the prize is on that line
+        let (dx, dy) = if (ax, ay) != (0, 0) { (ax, ay) } else { (bx, by) };
+        if mul(dx, prize_y)? != mul(dy, prize_x)? {
+            return Ok(None);
+        }
+        let presses = if dx != 0 {
+            cheapest_presses(ax, bx, prize_x)
+        } else {
+            cheapest_presses(ay, by, prize_y)
+        };
+        return presses.map(|(a, b)| add(mul(3, a)?, b)).transpose();
+    }
+    let a = mul(prize_x, by)?.abs_diff(mul(bx, prize_y)?) / determinant;
+    let b = mul(ax, prize_y)?.abs_diff(mul(prize_x, ay)?) / determinant;
+    if add(mul(ax, a)?, mul(bx, b)?)? == prize_x && add(mul(ay, a)?, mul(by, b)?)? == prize_y {
+        Ok(Some(add(mul(3, a)?, b)?))
     } else {
-        None
+        Ok(None)
     }
 }
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let b_offset = 10_000_000_000_000usize;
+/// Return the presses of A and B that move exactly `target` along a single axis for the fewest
+/// tokens, where A moves `a` and B moves `b` and A costs three times as much
+fn cheapest_presses(a: usize, b: usize, target: usize) -> Option<(usize, usize)> {
+    let (a, b, target) = (a as i128, b as i128, target as i128);
+    match (a, b) {
+        (0, 0) => return (target == 0).then_some((0, 0)),
+        (0, b) => return (target % b == 0).then_some((0, (target / b) as usize)),
+        (a, 0) => return (target % a == 0).then_some(((target / a) as usize, 0)),
+        _ => {}
+    }
 
-    let mut part_a = 0;
-    let mut part_b = 0;
-    let mut lines = input.lines();
-    while let Some((button_a_str, button_b_str, prize_str)) = lines.next_tuple() {
-        // Skip blank line if there is one
-        lines.next();
+    // Every solution is `(a0 + k * step_a, b0 - k * step_b)` for some `k`, and the cost changes by
+    // the same amount for each step, so the cheapest one uses as many of one button as possible
+    let (gcd, x, y) = extended_gcd(a, b);
+    if target % gcd != 0 {
+        return None;
+    }
+    let (a0, b0) = (x * (target / gcd), y * (target / gcd));
+    let (step_a, step_b) = (b / gcd, a / gcd);
+    let k_min = -a0.div_euclid(step_a);
+    let k_max = b0.div_euclid(step_b);
+    if k_min > k_max {
+        return None;
+    }
+    let k = if 3 * step_a > step_b { k_min } else { k_max };
+    Some(((a0 + k * step_a) as usize, (b0 - k * step_b) as usize))
+}
 
-        let (button_a_x, button_a_y) = parse_line(button_a_str)?;
-        let (button_b_x, button_b_y) = parse_line(button_b_str)?;
-        let (prize_x, prize_y) = parse_line(prize_str)?;
+/// Return `(gcd, x, y)` such that `a * x + b * y == gcd`
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        return (a, 1, 0);
+    }
+    let (gcd, x, y) = extended_gcd(b, a % b);
+    (gcd, y, x - (a / b) * y)
+}
 
-        part_a += cost_for_prize(
-            button_a_x, button_a_y, button_b_x, button_b_y, prize_x, prize_y,
-        )
-        .unwrap_or(0);
-        part_b += cost_for_prize(
+pub struct ClawMachine {
+    line: usize,
+    button_a: (usize, usize),
+    button_b: (usize, usize),
+    prize: (usize, usize),
+}
+
+impl ClawMachine {
+    fn cost(&self, prize_offset: usize) -> Result<Option<usize>> {
+        let (button_a_x, button_a_y) = self.button_a;
+        let (button_b_x, button_b_y) = self.button_b;
+        let (prize_x, prize_y) = self.prize;
+        let context = || format!("Day {} line {}", Day13::DAY, self.line);
+        cost_for_prize(
             button_a_x,
             button_a_y,
             button_b_x,
             button_b_y,
-            prize_x + b_offset,
-            prize_y + b_offset,
+            checked::add(prize_x, prize_offset).with_context(context)?,
+            checked::add(prize_y, prize_offset).with_context(context)?,
         )
-        .unwrap_or(0);
+        .with_context(context)
+    }
+}
+
+/// Return the number of tokens needed to win every prize that can be won
+fn total_cost(machines: &[ClawMachine], prize_offset: usize) -> Result<usize> {
+    let mut total = 0;
+    for machine in machines {
+        if let Some(cost) = machine.cost(prize_offset)? {
+            total = checked::add(total, cost)
+                .with_context(|| format!("Day {} line {}", Day13::DAY, machine.line))?;
+        }
+    }
+    Ok(total)
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: usize = 13;
+    const TITLE: &'static str = "Claw Contraption";
+
+    type Input = Vec<ClawMachine>;
+    type A = usize;
+    type B = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse::sections(input)
+            .into_iter()
+            .map(|section| {
+                let lines = section.lines(parse_line)?;
+                let [button_a, button_b, prize] = lines[..] else {
+                    return Err(anyhow!(
+                        "Expected 3 lines for the claw machine starting on line {}",
+                        section.first_line
+                    ));
+                };
+                Ok(ClawMachine {
+                    line: section.first_line,
+                    button_a,
+                    button_b,
+                    prize,
+                })
+            })
+            .collect()
+    }
+
+    fn part_a(machines: &Self::Input) -> Result<Self::A> {
+        total_cost(machines, 0)
+    }
+
+    fn part_b(machines: &Self::Input) -> Result<Option<Self::B>> {
+        let prize_offset = 10_000_000_000_000usize;
+        total_cost(machines, prize_offset).map(Some)
     }
-    Ok((part_a, Some(part_b)))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    test_real_input!(13, 37_297, 83_197_086_729_371);
+    test_real_input!(13);
+
+    #[test]
+    fn test_parallel_buttons() {
+        assert_eq!(cost_for_prize(1, 2, 2, 4, 3, 6).unwrap(), Some(4));
+        assert_eq!(cost_for_prize(1, 2, 2, 4, 4, 8).unwrap(), Some(2));
+        assert_eq!(cost_for_prize(1, 2, 2, 4, 3, 5).unwrap(), None);
+        assert_eq!(cost_for_prize(2, 4, 4, 8, 3, 6).unwrap(), None);
+        assert_eq!(cost_for_prize(0, 0, 0, 0, 3, 6).unwrap(), None);
+        assert_eq!(cost_for_prize(0, 0, 0, 0, 3, 0).unwrap(), None);
+        assert_eq!(cost_for_prize(0, 0, 0, 0, 0, 3).unwrap(), None);
+        assert_eq!(cost_for_prize(0, 0, 0, 0, 0, 0).unwrap(), Some(0));
+        assert_eq!(cost_for_prize(0, 0, 0, 3, 0, 6).unwrap(), Some(2));
+        assert_eq!(cost_for_prize(1, 0, 0, 1, 3, 6).unwrap(), Some(15));
+
+        // Button A is cheaper per step when it moves more than three times as far as button B
+        assert_eq!(cost_for_prize(6, 0, 1, 0, 13, 0).unwrap(), Some(7));
+        assert_eq!(cost_for_prize(2, 0, 1, 0, 13, 0).unwrap(), Some(13));
+        assert_eq!(cost_for_prize(4, 6, 6, 9, 10, 15).unwrap(), Some(4));
+    }
 }
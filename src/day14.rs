@@ -3,81 +3,136 @@ use std::collections::HashSet;
 use anyhow::{anyhow, Result};
 use regex::Regex;
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let re = Regex::new(r"^p=(-?\d+),(-?\d+) v=(-?\d+),(-?\d+)$").unwrap();
-    let mut robots: Vec<(isize, isize, isize, isize)> = Vec::new();
-    for line in input.lines() {
-        let Some((_, [x, y, dx, dy])) = re.captures(line).map(|c| c.extract()) else {
-            return Err(anyhow!("Failed to parse line {line:?}"));
+use crate::cancel;
+use crate::solution::Solution;
+use crate::utils::cycle::{find_cycle, Cycle};
+use crate::utils::parse;
+use crate::utils::point::Point;
+
+const SIZE: Point = Point::new(101, 103);
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Find how often the robots return to the same arrangement. The axes move independently, so the
+/// cycle of each axis is found on its own and the arrangement repeats when both have come around
+fn period(robots: &[(Point, Point)]) -> Cycle {
+    let axis_cycle = |coordinate: fn(Point) -> isize| {
+        let size = coordinate(SIZE);
+        let positions: Vec<_> = robots.iter().map(|&(p, _)| coordinate(p)).collect();
+        let step = |positions: &Vec<isize>| {
+            let moved = positions.iter().zip(robots);
+            Some(
+                moved
+                    .map(|(&x, &(_, velocity))| (x + coordinate(velocity)).rem_euclid(size))
+                    .collect(),
+            )
         };
-        robots.push((
-            x.parse().unwrap(),
-            y.parse().unwrap(),
-            dx.parse().unwrap(),
-            dy.parse().unwrap(),
-        ));
+        // Positions wrap around a finite grid, so the robots can't move without repeating
+        find_cycle(positions, step, Vec::clone).expect("Robots never stop moving")
+    };
+    let x = axis_cycle(|p| p.x);
+    let y = axis_cycle(|p| p.y);
+    Cycle {
+        start: x.start.max(y.start),
+        length: x.length / gcd(x.length, y.length) * y.length,
     }
+}
+
+/// Return the position of every robot after the given number of seconds
+fn positions_after(robots: &[(Point, Point)], seconds: usize) -> Vec<Point> {
+    let seconds = period(robots).equivalent(seconds) as isize;
+    robots
+        .iter()
+        .map(|&(p, velocity)| (p + velocity * seconds).rem_euclid(SIZE))
+        .collect()
+}
 
-    let width: isize = 101;
-    let height: isize = 103;
+pub struct Day14;
 
-    let mut part_a = None;
-    let mut part_b = None;
+impl Solution for Day14 {
+    const DAY: usize = 14;
+    const TITLE: &'static str = "Restroom Redoubt";
 
-    for i in 1.. {
-        if part_a.is_some() && part_b.is_some() {
-            break;
-        }
+    /// The position and velocity of every robot
+    type Input = Vec<(Point, Point)>;
+    type A = usize;
+    type B = usize;
 
-        let mut points = HashSet::new();
-        for (x, y, dx, dy) in robots.iter_mut() {
-            *x = (*x + *dx).rem_euclid(width);
-            *y = (*y + *dy).rem_euclid(height);
+    fn parse(input: &str) -> Result<Self::Input> {
+        let re = Regex::new(r"^p=(-?\d+),(-?\d+) v=(-?\d+),(-?\d+)$").unwrap();
+        parse::lines(input, |line| {
+            let [x, y, dx, dy] = parse::captures(&re, line)?;
+            Ok((
+                Point::new(parse::value(x)?, parse::value(y)?),
+                Point::new(parse::value(dx)?, parse::value(dy)?),
+            ))
+        })
+    }
 
-            points.insert((*x, *y));
+    fn part_a(robots: &Self::Input) -> Result<Self::A> {
+        let mut quadrants = [0usize; 4];
+        let h = SIZE.x / 2;
+        let v = SIZE.y / 2;
+        for Point { x, y } in positions_after(robots, 100) {
+            quadrants[0] += usize::from(x < h && y < v);
+            quadrants[1] += usize::from(x > h && y < v);
+            quadrants[2] += usize::from(x < h && y > v);
+            quadrants[3] += usize::from(x > h && y > v);
         }
+        Ok(quadrants.into_iter().product())
+    }
+
+    fn part_b(robots: &Self::Input) -> Result<Option<Self::B>> {
+        let mut robots = robots.to_vec();
 
-        if i == 100 {
-            let mut quadrants = [0usize; 4];
-            let h = width / 2;
-            let v = height / 2;
-            for (x, y, _, _) in robots.iter().copied() {
-                quadrants[0] += usize::from(x < h && y < v);
-                quadrants[1] += usize::from(x > h && y < v);
-                quadrants[2] += usize::from(x < h && y > v);
-                quadrants[3] += usize::from(x > h && y > v);
+        // Every arrangement has been seen once a full period has passed
+        let token = cancel::current();
+        for i in 1..period(&robots).length {
+            token.check()?;
+            let mut points = HashSet::new();
+            for (p, velocity) in robots.iter_mut() {
+                *p = (*p + *velocity).rem_euclid(SIZE);
+                points.insert(*p);
             }
-            part_a = Some(quadrants.into_iter().product());
-        }
 
-        // The trunk has a 3x3 and it seems like this will only trigger for the easter egg frame
-        for (x, y) in points.iter().copied() {
-            let neighbors = [
-                (x - 1, y - 1),
-                (x - 1, y),
-                (x - 1, y + 1),
-                (x, y - 1),
-                (x, y + 1),
-                (x + 1, y - 1),
-                (x + 1, y),
-                (x + 1, y + 1),
-            ];
-            let has_christmas_tree = neighbors.into_iter().all(|p| points.contains(&p));
-            if has_christmas_tree {
-                // Only register the first occurrence
-                part_b = part_b.or(Some(i));
+            // The trunk has a 3x3 and it seems like this will only trigger for the easter egg frame
+            for p in points.iter().copied() {
+                let has_christmas_tree = p.neighbors8().iter().all(|n| points.contains(n));
+                if has_christmas_tree {
+                    return Ok(Some(i));
+                }
             }
         }
-    }
 
-    Ok((
-        part_a.ok_or_else(|| anyhow!("No solution for part A"))?,
-        Some(part_b.ok_or_else(|| anyhow!("No solution for part B"))?),
-    ))
+        Err(anyhow!("No solution for part B"))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    test_real_input!(14, 231_782_040, 6475);
+
+    test_real_input!(14);
+
+    #[test]
+    fn test_positions_after() {
+        let robots = Day14::parse("p=2,4 v=2,-3\np=0,0 v=-1,-1").unwrap();
+        assert_eq!(
+            positions_after(&robots, 1),
+            [Point::new(4, 1), Point::new(100, 102)]
+        );
+        let period = period(&robots);
+        assert_eq!(period.start, 0);
+        assert_eq!(positions_after(&robots, period.length), positions_after(&robots, 0));
+        assert_eq!(
+            positions_after(&robots, 1_000_000_000),
+            positions_after(&robots, 1_000_000_000 % period.length)
+        );
+    }
 }
@@ -1,7 +1,10 @@
-use anyhow::{Context as _, Result};
+use anyhow::Result;
 
 use itertools::Itertools;
 
+use crate::solution::Solution;
+use crate::utils::parse;
+
 fn is_report_safe(report: impl AsRef<[usize]>, skip: Option<usize>) -> bool {
     let mut was_ascending = None;
     for (prev, curr) in report
@@ -23,54 +26,62 @@ fn is_report_safe(report: impl AsRef<[usize]>, skip: Option<usize>) -> bool {
     true
 }
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let mut part_a = 0;
-    let mut part_b = 0;
-    for (i, line) in input.lines().enumerate() {
-        let report = line
-            .split_whitespace()
-            .map(|v| Ok(v.parse()?))
-            .collect::<Result<Vec<usize>>>()
-            .with_context(|| format!("Failed to parse line {}", i + 1))?;
-        if is_report_safe(&report, None) {
-            // Report is valid for both part a and b
-            part_a += 1;
-        } else if (0..report.len()).any(|i| is_report_safe(&report, Some(i))) {
-            // Report is only valid for part b
-            part_b += 1;
-        }
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: usize = 2;
+    const TITLE: &'static str = "Red-Nosed Reports";
+
+    type Input = Vec<Vec<usize>>;
+    type A = usize;
+    type B = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse::lines(input, parse::integers)
     }
 
-    // Add reports that are valid to part b, as we only count the ones that are valid only with the
-    // problem dampener
-    part_b += part_a;
+    fn part_a(reports: &Self::Input) -> Result<Self::A> {
+        Ok(reports
+            .iter()
+            .filter(|report| is_report_safe(report, None))
+            .count())
+    }
 
-    Ok((part_a, Some(part_b)))
+    fn part_b(reports: &Self::Input) -> Result<Option<Self::B>> {
+        // A report is safe with the problem dampener if it's safe as is, or when skipping one level
+        Ok(Some(reports
+            .iter()
+            .filter(|report| {
+                is_report_safe(report, None)
+                    || (0..report.len()).any(|i| is_report_safe(report, Some(i)))
+            })
+            .count()))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    test_real_input!(2, 639, 674);
+    test_real_input!(2);
 
     #[test]
     fn test_without_dampener() {
-        assert!(is_report_safe(&[7, 6, 4, 2, 1], None));
-        assert!(!is_report_safe(&[1, 2, 7, 8, 9], None));
-        assert!(!is_report_safe(&[9, 7, 6, 2, 1], None));
-        assert!(!is_report_safe(&[1, 3, 2, 4, 5], None));
-        assert!(!is_report_safe(&[8, 6, 4, 4, 1], None));
-        assert!(is_report_safe(&[1, 3, 6, 7, 9], None));
+        assert!(is_report_safe([7, 6, 4, 2, 1], None));
+        assert!(!is_report_safe([1, 2, 7, 8, 9], None));
+        assert!(!is_report_safe([9, 7, 6, 2, 1], None));
+        assert!(!is_report_safe([1, 3, 2, 4, 5], None));
+        assert!(!is_report_safe([8, 6, 4, 4, 1], None));
+        assert!(is_report_safe([1, 3, 6, 7, 9], None));
     }
 
     #[test]
     fn test_with_dampener() {
-        assert!(is_report_safe(&[1, 3, 2, 4, 5], Some(1)));
-        assert!(is_report_safe(&[8, 6, 4, 4, 1], Some(2)));
+        assert!(is_report_safe([1, 3, 2, 4, 5], Some(1)));
+        assert!(is_report_safe([8, 6, 4, 4, 1], Some(2)));
 
         // Edge case where the first value needs to be skipped to make the report valid
-        assert!(!is_report_safe(&[8, 4, 5, 6, 7], None));
-        assert!(is_report_safe(&[8, 4, 5, 6, 7], Some(0)));
+        assert!(!is_report_safe([8, 4, 5, 6, 7], None));
+        assert!(is_report_safe([8, 4, 5, 6, 7], Some(0)));
     }
 }
@@ -2,6 +2,9 @@ use anyhow::{anyhow, Result};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 
+use crate::solution::Solution;
+use crate::utils::parse;
+
 fn is_valid(rules: &HashMap<usize, HashSet<usize>>, update: &[usize]) -> bool {
     for (i, v) in update.iter().enumerate() {
         if rules
@@ -15,74 +18,78 @@ fn is_valid(rules: &HashMap<usize, HashSet<usize>>, update: &[usize]) -> bool {
     true
 }
 
-fn part_a(rules: &HashMap<usize, HashSet<usize>>, updates: &[impl AsRef<[usize]>]) -> usize {
-    let mut n = 0;
-    for update in updates
-        .iter()
-        .map(AsRef::as_ref)
-        .filter(|update| is_valid(rules, update))
-    {
-        n += update[update.len() / 2];
-    }
-    n
+pub struct Manual {
+    rules: HashMap<usize, HashSet<usize>>,
+    updates: Vec<Vec<usize>>,
 }
 
-fn part_b(rules: &HashMap<usize, HashSet<usize>>, updates: &[impl AsRef<[usize]>]) -> usize {
-    let mut n = 0;
-    for update in updates.iter().map(AsRef::as_ref) {
-        if is_valid(rules, update) {
-            continue;
-        }
+pub struct Day5;
 
-        let mut reordered_update = update.to_vec();
-        reordered_update.sort_by(|a, b| {
-            if rules.get(a).map(|after| after.contains(b)).unwrap_or(false) {
-                Ordering::Less
-            } else {
-                Ordering::Equal
-            }
-        });
+impl Solution for Day5 {
+    const DAY: usize = 5;
+    const TITLE: &'static str = "Print Queue";
 
-        if is_valid(rules, &reordered_update) {
-            n += reordered_update[reordered_update.len() / 2];
+    type Input = Manual;
+    type A = usize;
+    type B = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let [rules_section, updates_section] = parse::sections(input)[..] else {
+            return Err(anyhow!("Expected rules and updates separated by a blank line"));
+        };
+
+        let mut rules: HashMap<usize, HashSet<usize>> = HashMap::new();
+        for (before, after) in rules_section.lines(|l| parse::pair(l, "|"))? {
+            rules.entry(before).or_default().insert(after);
         }
+        let updates = updates_section.lines(|l| parse::separated(l, ","))?;
+
+        Ok(Manual { rules, updates })
     }
-    n
-}
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let (rules_str, update_str) = input
-        .split_once("\n\n")
-        .ok_or_else(|| anyhow!("Failed to split rules and updates"))?;
-
-    let mut rules: HashMap<usize, HashSet<usize>> = HashMap::new();
-    for rule_str in rules_str.lines() {
-        let (before, after) = rule_str
-            .split_once('|')
-            .ok_or_else(|| anyhow!("No | found in rule"))?;
-        rules
-            .entry(before.parse()?)
-            .or_default()
-            .insert(after.parse()?);
+    fn part_a(manual: &Self::Input) -> Result<Self::A> {
+        let rules = &manual.rules;
+        let mut n = 0;
+        for update in manual
+            .updates
+            .iter()
+            .filter(|update| is_valid(rules, update))
+        {
+            n += update[update.len() / 2];
+        }
+        Ok(n)
     }
 
-    let updates = update_str
-        .lines()
-        .map(|l| {
-            l.split(',')
-                .map(|i| Ok(i.parse()?))
-                .collect::<Result<Vec<usize>>>()
-        })
-        .collect::<Result<Vec<Vec<usize>>>>()?;
+    fn part_b(manual: &Self::Input) -> Result<Option<Self::B>> {
+        let rules = &manual.rules;
+        let mut n = 0;
+        for update in manual.updates.iter() {
+            if is_valid(rules, update) {
+                continue;
+            }
+
+            let mut reordered_update = update.to_vec();
+            reordered_update.sort_by(|a, b| {
+                if rules.get(a).map(|after| after.contains(b)).unwrap_or(false) {
+                    Ordering::Less
+                } else {
+                    Ordering::Equal
+                }
+            });
 
-    Ok((part_a(&rules, &updates), Some(part_b(&rules, &updates))))
+            if is_valid(rules, &reordered_update) {
+                n += reordered_update[reordered_update.len() / 2];
+            }
+        }
+        Ok(Some(n))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    test_real_input!(5, 4774, 6004);
+    test_real_input!(5);
 
     const EXAMPLE: &str = dedent::dedent!(
         r#"
@@ -117,8 +124,8 @@ mod test {
         "#
     );
 
-    #[test]
-    fn test_example() {
-        assert_eq!(main(EXAMPLE).unwrap(), (143, Some(123)));
+    test_examples! {
+        Day5,
+        test_example: EXAMPLE => (143, 123),
     }
 }
@@ -3,113 +3,107 @@ use std::collections::HashSet;
 
 use rayon::prelude::*;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
+use crate::cancel;
+use crate::solution::Solution;
+use crate::utils::cycle::brent;
+use crate::utils::grid::Grid;
+use crate::utils::point::{Direction, Point};
+
+pub struct Lab {
+    obstacles: Grid<bool>,
+    guard: Point,
 }
 
-impl Direction {
-    fn turn_right(&self) -> Self {
-        match self {
-            Self::Up => Self::Right,
-            Self::Right => Self::Down,
-            Self::Down => Self::Left,
-            Self::Left => Self::Up,
-        }
+impl Lab {
+    fn contains(&self, p: Point) -> bool {
+        self.obstacles.contains(p)
     }
 
-    fn advance(&self, (x, y): (isize, isize)) -> (isize, isize) {
-        match self {
-            Self::Up => (x, y - 1),
-            Self::Right => (x + 1, y),
-            Self::Down => (x, y + 1),
-            Self::Left => (x - 1, y),
-        }
+    fn is_obstacle(&self, p: Point) -> bool {
+        self.obstacles.get(p) == Some(&true)
     }
 }
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let mut max_x = 0;
-    let mut max_y = 0;
-    let mut guard = None;
-    let mut map = HashSet::new();
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '#' => {
-                    map.insert((x as isize, y as isize));
-                }
-                '^' => guard = Some((x as isize, y as isize)),
-                _ => {}
-            }
-            max_x = max_x.max(x as isize);
-        }
-        max_y = max_y.max(y as isize);
-    }
-
-    let guard = guard.ok_or_else(|| anyhow!("No guard start location found"))?;
-    let x_bounds = 0..=max_x;
-    let y_bounds = 0..=max_y;
-
-    // Find the path of the guard
-    let mut visited = HashSet::new();
+/// Return every position and direction the guard has while walking out of the lab
+fn patrol(lab: &Lab) -> Result<HashSet<(Direction, Point)>> {
     let mut visited_with_direction = HashSet::new();
-    let (mut x, mut y) = guard;
+    let mut p = lab.guard;
     let mut dir = Direction::Up;
-    while x_bounds.contains(&x) && y_bounds.contains(&y) {
-        visited.insert((x, y));
-        if !visited_with_direction.insert((dir, x, y)) {
+    while lab.contains(p) {
+        if !visited_with_direction.insert((dir, p)) {
             return Err(anyhow!("Loop found without altering the map"));
         }
-        let (nx, ny) = dir.advance((x, y));
-        if map.contains(&(nx, ny)) {
+        let next = p.step(dir);
+        if lab.is_obstacle(next) {
             dir = dir.turn_right();
             continue;
         }
-        (x, y) = (nx, ny);
+        p = next;
     }
+    Ok(visited_with_direction)
+}
+
+pub struct Day6;
 
-    let visited_positions = visited.len();
-
-    // Use the original path to determine where we should try to inject obstacles
-    let obstacles_to_try: HashSet<_> = visited_with_direction
-        .into_iter()
-        .map(|(dir, x, y)| dir.advance((x, y)))
-        .filter(|(x, y)| x_bounds.contains(x) && y_bounds.contains(y))
-        .collect();
-    let num_possible_obstacle_positions = obstacles_to_try
-        .into_par_iter()
-        .map(|(ox, oy)| {
-            let mut dir = Direction::Up;
-            let (mut x, mut y) = guard;
-
-            let mut visited = HashSet::new();
-            while x_bounds.contains(&x) && y_bounds.contains(&y) {
-                if !visited.insert((dir, x, y)) {
-                    return 1;
-                }
-                let (nx, ny) = dir.advance((x, y));
-                if (nx == ox && ny == oy) || map.contains(&(nx, ny)) {
-                    dir = dir.turn_right();
-                    continue;
-                }
-                (x, y) = (nx, ny);
-            }
-            0
+impl Solution for Day6 {
+    const DAY: usize = 6;
+    const TITLE: &'static str = "Guard Gallivant";
+
+    type Input = Lab;
+    type A = usize;
+    type B = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let map = Grid::parse(input, Ok)?;
+        Ok(Lab {
+            obstacles: map.map(|&c| c == '#'),
+            guard: map
+                .find(&'^')
+                .ok_or_else(|| anyhow!("No guard start location found"))?,
         })
-        .sum();
+    }
+
+    fn part_a(lab: &Self::Input) -> Result<Self::A> {
+        let visited: HashSet<_> = patrol(lab)?.into_iter().map(|(_, p)| p).collect();
+        Ok(visited.len())
+    }
+
+    fn part_b(lab: &Self::Input) -> Result<Option<Self::B>> {
+        // Use the original path to determine where we should try to inject obstacles
+        let obstacles_to_try: HashSet<_> = patrol(lab)?
+            .into_iter()
+            .map(|(dir, p)| p.step(dir))
+            .filter(|&p| lab.contains(p))
+            .collect();
+        let token = cancel::current();
+        let num_possible_obstacle_positions = obstacles_to_try
+            .into_par_iter()
+            .map(|obstacle| {
+                token.check()?;
 
-    Ok((visited_positions, Some(num_possible_obstacle_positions)))
+                // The guard is stuck in a loop if their walk repeats before leaving the lab
+                let walk = brent((Direction::Up, lab.guard), |&(dir, p)| {
+                    let next = p.step(dir);
+                    if !lab.contains(next) {
+                        None
+                    } else if next == obstacle || lab.is_obstacle(next) {
+                        Some((dir.turn_right(), p))
+                    } else {
+                        Some((dir, next))
+                    }
+                });
+                Ok(usize::from(walk.is_some()))
+            })
+            .sum::<Result<usize>>()?;
+
+        Ok(Some(num_possible_obstacle_positions))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    test_real_input!(6, 5086, 1770);
+    test_real_input!(6);
 
     const EXAMPLE: &str = dedent::dedent!(
         r#"
@@ -126,8 +120,8 @@ mod test {
         "#
     );
 
-    #[test]
-    fn test_example() {
-        assert_eq!(main(EXAMPLE).unwrap(), (41, Some(6)));
+    test_examples! {
+        Day6,
+        test_example: EXAMPLE => (41, 6),
     }
 }
@@ -1,65 +1,102 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Context as _, Result};
 
 use rayon::prelude::*;
 
-fn parse_eq(l: &str) -> Result<(usize, Vec<usize>)> {
-    let Some((id_str, eq_str)) = l.split_once(": ") else {
+use crate::cancel;
+use crate::solution::Solution;
+use crate::utils::{checked, parse};
+
+fn parse_eq(line: &str) -> Result<(usize, Vec<usize>)> {
+    let Some((test_value, nums)) = line.split_once(": ") else {
         return Err(anyhow!("No separator between test value and numbers found"));
     };
-    let nums = eq_str
-        .split(' ')
-        .map(|s| Ok(s.parse()?))
-        .collect::<Result<Vec<_>>>()?;
-    Ok((id_str.parse()?, nums))
+    let nums = parse::integers_in(line, nums)?;
+    if nums.is_empty() {
+        return Err(anyhow!("No numbers found after the test value"));
+    }
+    Ok((parse::value(test_value)?, nums))
 }
 
-fn is_valid_eq(test_value: usize, nums: &[usize], use_concat: bool) -> bool {
+fn is_valid_eq(test_value: usize, nums: &[usize], use_concat: bool) -> Result<bool> {
     let num_operators: usize = if use_concat { 3 } else { 2 };
-    let num_combinations = num_operators.pow((nums.len() - 1) as u32);
-    (0..num_combinations).into_par_iter().any(|mut ops| {
-        let mut it = nums.iter().copied();
-        let Some(mut acc) = it.next() else {
+    let num_combinations = checked::pow(num_operators, nums.len().saturating_sub(1) as u32)?;
+
+    // Every operator makes the value larger, unless a later number is zero
+    let last_zero = nums.iter().rposition(|&n| n == 0);
+
+    Ok((0..num_combinations).into_par_iter().any(|mut ops| {
+        let mut it = nums.iter().copied().enumerate();
+        let Some((_, first)) = it.next() else {
             return false;
         };
-        for curr in it {
-            match ops % num_operators {
-                0 => acc += curr,
-                1 => acc *= curr,
-                2 => {
+
+        // A candidate that overflows is `None`, which can still match if it's multiplied by zero
+        let mut acc = Some(first);
+        for (i, curr) in it {
+            let op = ops % num_operators;
+            ops /= num_operators;
+            acc = match (op, acc) {
+                (1, _) if curr == 0 => Some(0),
+                (_, None) => None,
+                (0, Some(acc)) => checked::add(acc, curr).ok(),
+                (1, Some(acc)) => checked::mul(acc, curr).ok(),
+                (2, Some(acc)) => {
                     let num_digits = curr.checked_ilog10().unwrap_or(0) + 1;
-                    acc = acc * 10usize.pow(num_digits) + curr;
+                    checked::pow(10, num_digits)
+                        .and_then(|shift| checked::mul(acc, shift))
+                        .and_then(|acc| checked::add(acc, curr))
+                        .ok()
                 }
                 _ => unreachable!(),
+            };
+            if acc.is_none_or(|acc| acc > test_value) && last_zero.is_none_or(|zero| zero <= i) {
+                return false;
             }
-            ops /= num_operators;
-        }
-        if acc == test_value {
-            return true;
         }
-        false
-    })
+        acc == Some(test_value)
+    }))
 }
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let mut a = 0;
-    let mut b = 0;
-
-    for (i, line) in input.lines().enumerate() {
-        let (test_value, nums) =
-            parse_eq(line).with_context(|| anyhow!("Failed to read line {}", i + 1))?;
-        if is_valid_eq(test_value, &nums, false) {
-            a += test_value;
-        } else if is_valid_eq(test_value, &nums, true) {
-            b += test_value;
+fn calibration_result(equations: &[(usize, Vec<usize>)], use_concat: bool) -> Result<usize> {
+    let token = cancel::current();
+    let mut result = 0;
+    for (i, (test_value, nums)) in equations.iter().enumerate() {
+        token.check()?;
+        let context = || format!("Day {} line {}", Day7::DAY, i + 1);
+        if is_valid_eq(*test_value, nums, use_concat).with_context(context)? {
+            result = checked::add(result, *test_value).with_context(context)?;
         }
     }
-    Ok((a, Some(a + b)))
+    Ok(result)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: usize = 7;
+    const TITLE: &'static str = "Bridge Repair";
+
+    type Input = Vec<(usize, Vec<usize>)>;
+    type A = usize;
+    type B = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse::lines(input, parse_eq)
+    }
+
+    fn part_a(equations: &Self::Input) -> Result<Self::A> {
+        calibration_result(equations, false)
+    }
+
+    fn part_b(equations: &Self::Input) -> Result<Option<Self::B>> {
+        calibration_result(equations, true).map(Some)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    test_real_input!(7, 1_289_579_105_366, 92_148_721_834_692);
+    test_real_input!(7);
 
     const EXAMPLE: &str = dedent::dedent!(
         r#"
@@ -75,8 +112,26 @@ mod test {
         "#
     );
 
+    test_examples! {
+        Day7,
+        test_example: EXAMPLE => (3749, 11387),
+    }
+
+    #[test]
+    fn test_zero_after_large_value() {
+        assert!(is_valid_eq(3, &[5, 7, 0, 3], false).unwrap());
+        assert!(!is_valid_eq(3, &[5, 7, 3], false).unwrap());
+    }
+
+    #[cfg(feature = "checked")]
     #[test]
-    fn test_example() {
-        assert_eq!(main(EXAMPLE).unwrap(), (3749, Some(11387)));
+    fn test_overflow_is_no_match() {
+        let max = usize::MAX;
+        assert!(!is_valid_eq(5, &[max, 2], true).unwrap());
+        assert!(is_valid_eq(max, &[max - 1, 1], false).unwrap());
+
+        // Multiplying by zero brings a candidate that overflowed back
+        assert!(is_valid_eq(3, &[max, 2, 0, 3], false).unwrap());
+        assert!(!is_valid_eq(3, &[max, 2, 1, 3], false).unwrap());
     }
 }
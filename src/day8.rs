@@ -1,92 +1,100 @@
 use anyhow::Result;
 use std::collections::{HashMap, HashSet};
-use std::ops::RangeInclusive;
 
 use itertools::Itertools;
 
-fn part_a(
-    antennas_by_freq: &HashMap<char, HashSet<(isize, isize)>>,
-    x_bounds: RangeInclusive<isize>,
-    y_bounds: RangeInclusive<isize>,
-) -> usize {
-    let mut antinodes = HashSet::new();
-    for antennas in antennas_by_freq.values() {
-        for ((ax, ay), (bx, by)) in antennas.iter().tuple_combinations() {
-            let (dx, dy) = (ax - bx, ay - by);
-            for (ax, ay) in [(ax + dx, ay + dy), (bx - dx, by - dy)] {
-                if x_bounds.contains(&ax) && y_bounds.contains(&ay) {
-                    antinodes.insert((ax, ay));
-                }
-            }
-        }
-    }
-    antinodes.len()
+use crate::solution::Solution;
+use crate::utils::grid::Grid;
+use crate::utils::point::Point;
+
+pub struct City {
+    map: Grid<char>,
+    antennas_by_freq: HashMap<char, HashSet<Point>>,
 }
 
-fn part_b(
-    antennas_by_freq: &HashMap<char, HashSet<(isize, isize)>>,
-    x_bounds: RangeInclusive<isize>,
-    y_bounds: RangeInclusive<isize>,
-) -> usize {
-    let mut antinodes = HashSet::new();
-    for antennas in antennas_by_freq.values() {
-        for ((ax, ay), (bx, by)) in antennas.iter().tuple_combinations() {
-            let (dx, dy) = (ax - bx, ay - by);
+pub struct Day8;
 
-            for step in 0.. {
-                let (ax, ay) = (ax + step * dx, ay + step * dy);
-                if !x_bounds.contains(&ax) || !y_bounds.contains(&ay) {
-                    break;
-                }
-                antinodes.insert((ax, ay));
-            }
+impl Solution for Day8 {
+    const DAY: usize = 8;
+    const TITLE: &'static str = "Resonant Collinearity";
 
-            for step in 0.. {
-                let (ax, ay) = (bx - step * dx, by - step * dy);
-                if !x_bounds.contains(&ax) || !y_bounds.contains(&ay) {
-                    break;
-                }
-                antinodes.insert((ax, ay));
-            }
-        }
-    }
-    antinodes.len()
-}
+    type Input = City;
+    type A = usize;
+    type B = usize;
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let mut max_x = 0;
-    let mut max_y = 0;
-    let mut antennas_by_freq = HashMap::new();
-    for (y, line) in input.lines().enumerate() {
-        let y = y as isize;
-        max_y = max_y.max(y);
-        for (x, c) in line.chars().enumerate() {
-            let x = x as isize;
-            max_x = max_x.max(x);
+    fn parse(input: &str) -> Result<Self::Input> {
+        let map = Grid::parse(input, Ok)?;
+        let mut antennas_by_freq = HashMap::new();
+        for (p, &c) in map.iter() {
             if c == '.' || c == '#' {
                 continue;
             }
             antennas_by_freq
                 .entry(c)
                 .or_insert_with(HashSet::new)
-                .insert((x, y));
+                .insert(p);
         }
+        Ok(City {
+            map,
+            antennas_by_freq,
+        })
     }
 
-    let x_bounds = 0..=max_x;
-    let y_bounds = 0..=max_y;
+    fn part_a(city: &Self::Input) -> Result<Self::A> {
+        let City {
+            map,
+            antennas_by_freq,
+        } = city;
+        let mut antinodes = HashSet::new();
+        for antennas in antennas_by_freq.values() {
+            for (&a, &b) in antennas.iter().tuple_combinations() {
+                let delta = a - b;
+                for antinode in [a + delta, b - delta] {
+                    if map.contains(antinode) {
+                        antinodes.insert(antinode);
+                    }
+                }
+            }
+        }
+        Ok(antinodes.len())
+    }
+
+    fn part_b(city: &Self::Input) -> Result<Option<Self::B>> {
+        let City {
+            map,
+            antennas_by_freq,
+        } = city;
+        let mut antinodes = HashSet::new();
+        for antennas in antennas_by_freq.values() {
+            for (&a, &b) in antennas.iter().tuple_combinations() {
+                let delta = a - b;
 
-    Ok((
-        part_a(&antennas_by_freq, x_bounds.clone(), y_bounds.clone()),
-        Some(part_b(&antennas_by_freq, x_bounds, y_bounds)),
-    ))
+                for step in 0.. {
+                    let antinode = a + delta * step;
+                    if !map.contains(antinode) {
+                        break;
+                    }
+                    antinodes.insert(antinode);
+                }
+
+                for step in 0.. {
+                    let antinode = b - delta * step;
+                    if !map.contains(antinode) {
+                        break;
+                    }
+                    antinodes.insert(antinode);
+                }
+            }
+        }
+        Ok(Some(antinodes.len()))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    test_real_input!(8, 276, 991);
+    test_real_input!(8);
 
     const EXAMPLE: &str = dedent::dedent!(
         r#"
@@ -105,8 +113,8 @@ mod test {
         "#
     );
 
-    #[test]
-    fn test_example() {
-        assert_eq!(main(EXAMPLE).unwrap(), (14, Some(34)));
+    test_examples! {
+        Day8,
+        test_example: EXAMPLE => (14, 34),
     }
 }
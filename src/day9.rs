@@ -1,93 +1,97 @@
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 
 use itertools::Itertools;
 
-fn part_a(blocks: Vec<Option<usize>>) -> usize {
-    let num_used_blocks = blocks.iter().filter(|block| block.is_some()).count();
-    let mut rev_blocks = blocks.iter().copied().rev().flatten();
-    let mut compacted = Vec::new();
-    for block in blocks.iter().copied().take(num_used_blocks) {
-        match block {
-            Some(id) => compacted.push(id),
-            None => {
-                compacted.push(rev_blocks.next().unwrap());
-            }
-        }
-    }
+use crate::cancel;
+use crate::solution::Solution;
+use crate::utils::parse;
 
-    compacted.iter().enumerate().map(|(i, a)| i * a).sum()
-}
+pub struct Day9;
 
-fn part_b(mut blocks: Vec<Option<usize>>) -> usize {
-    let mut end = blocks.len();
-    while end > 0 {
-        // Find start
-        let id = blocks[end - 1];
-        let len = blocks[..end]
-            .iter()
-            .rev()
-            .take_while(|block| block == &&id)
-            .count();
-        let start = end - len;
-        let (a, b) = blocks.split_at_mut(start);
-        end = start;
+impl Solution for Day9 {
+    const DAY: usize = 9;
+    const TITLE: &'static str = "Disk Fragmenter";
 
-        // Find suitable place to insert
-        for free_start in 0..=(a.len().saturating_sub(len)) {
-            let target = &mut a[free_start..];
-            if target.len() < len || target[..len].iter().any(Option::is_some) {
-                continue;
-            }
-            target[..len].swap_with_slice(&mut b[..len]);
-        }
-    }
-    blocks
-        .iter()
-        .enumerate()
-        .map(|(i, a)| i * a.unwrap_or(0))
-        .sum()
-}
+    type Input = Vec<Option<usize>>;
+    type A = usize;
+    type B = usize;
 
-pub fn main(input: &str) -> Result<(usize, Option<usize>)> {
-    let mut blocks = Vec::new();
-    for (id, (used, free)) in input
-        .trim_end()
-        .chars()
-        .chain(std::iter::once('0'))
-        .tuples()
-        .enumerate()
-    {
-        for _ in 0..used
-            .to_digit(10)
-            .ok_or_else(|| anyhow!("Invalid used specifier {}", used))?
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut blocks = Vec::new();
+        let disk_map = parse::digits(input.trim_end())?;
+        for (id, (used, free)) in disk_map
+            .into_iter()
+            .chain(std::iter::once(0))
+            .tuples()
+            .enumerate()
         {
-            blocks.push(Some(id));
+            blocks.extend(std::iter::repeat_n(Some(id), used as usize));
+            blocks.extend(std::iter::repeat_n(None, free as usize));
         }
 
-        for _ in 0..free
-            .to_digit(10)
-            .ok_or_else(|| anyhow!("Invalid free specifier {}", free))?
-        {
-            blocks.push(None);
+        Ok(blocks)
+    }
+
+    fn part_a(blocks: &Self::Input) -> Result<Self::A> {
+        let num_used_blocks = blocks.iter().filter(|block| block.is_some()).count();
+        let mut rev_blocks = blocks.iter().copied().rev().flatten();
+        let mut compacted = Vec::new();
+        for block in blocks.iter().copied().take(num_used_blocks) {
+            match block {
+                Some(id) => compacted.push(id),
+                None => {
+                    compacted.push(rev_blocks.next().unwrap());
+                }
+            }
         }
+
+        Ok(compacted.iter().enumerate().map(|(i, a)| i * a).sum())
     }
 
-    Ok((part_a(blocks.clone()), Some(part_b(blocks))))
+    fn part_b(blocks: &Self::Input) -> Result<Option<Self::B>> {
+        let token = cancel::current();
+        let mut blocks = blocks.to_vec();
+        let mut end = blocks.len();
+        while end > 0 {
+            token.check()?;
+
+            // Find start
+            let id = blocks[end - 1];
+            let len = blocks[..end]
+                .iter()
+                .rev()
+                .take_while(|block| block == &&id)
+                .count();
+            let start = end - len;
+            let (a, b) = blocks.split_at_mut(start);
+            end = start;
+
+            // Find suitable place to insert
+            for free_start in 0..=(a.len().saturating_sub(len)) {
+                let target = &mut a[free_start..];
+                if target.len() < len || target[..len].iter().any(Option::is_some) {
+                    continue;
+                }
+                target[..len].swap_with_slice(&mut b[..len]);
+            }
+        }
+        Ok(Some(blocks
+            .iter()
+            .enumerate()
+            .map(|(i, a)| i * a.unwrap_or(0))
+            .sum()))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    test_real_input!(9, 6_200_294_120_911, 6_227_018_762_750);
-
-    #[test]
-    fn test_example() {
-        assert_eq!(main("2333133121414131402").unwrap(), (1928, Some(2858)));
-    }
+    test_real_input!(9);
 
-    #[test]
-    fn test_edge_case() {
+    test_examples! {
+        Day9,
+        test_example: "2333133121414131402" => (1928, 2858),
         // https://www.reddit.com/r/adventofcode/comments/1hamyyn/2024_day_9_part_2_python/
-        assert_eq!(main("2333133121414131499").unwrap(), (3630, Some(6204)));
+        test_edge_case: "2333133121414131499" => (3630, 6204),
     }
 }
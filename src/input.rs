@@ -0,0 +1,191 @@
+use anyhow::{anyhow, Context as _, Result};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read as _};
+use std::path::{Path, PathBuf};
+
+/// Where the puzzle input is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// A file on disk
+    File(PathBuf),
+
+    /// Standard input, which is given as `-` on the command line
+    Stdin,
+
+    /// Text given directly on the command line
+    Inline(String),
+}
+
+impl Source {
+    /// The default input for a day, which is `data/day<num>.txt`
+    pub fn for_day(day: usize) -> Self {
+        Self::File(default_path(day))
+    }
+
+    /// Interpret a path from the command line, where `-` means standard input
+    pub fn from_path(path: PathBuf) -> Self {
+        if path.as_os_str() == "-" {
+            Self::Stdin
+        } else {
+            Self::File(path)
+        }
+    }
+
+    /// Read the input. Windows line endings are converted so that solutions splitting on blank
+    /// lines work with pasted input as well
+    pub fn read(&self) -> Result<String> {
+        let input = match self {
+            Self::File(path) => fs::read_to_string(path)
+                .with_context(|| format!("Failed to open input file {:?}", path))?,
+            Self::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Failed to read input from stdin")?;
+                input
+            }
+            Self::Inline(input) => input.clone(),
+        };
+        Ok(normalize_newlines(input))
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Stdin => write!(f, "<stdin>"),
+            Self::Inline(_) => write!(f, "<example>"),
+        }
+    }
+}
+
+/// The path of the real puzzle input for a day
+pub fn default_path(day: usize) -> PathBuf {
+    format!("data/day{}.txt", day).into()
+}
+
+/// The extension of the files with expected answers that sit next to extra inputs
+const SIDECAR_EXTENSION: &str = "answers";
+
+/// The extra inputs for a day, which are all files in `data/day<num>/` except for expected answers
+pub fn day_inputs(day: usize) -> Result<Vec<PathBuf>> {
+    glob(&format!("data/day{}/*", day))
+}
+
+/// Return the files matching the pattern, ordered by name. Only the file name may contain the
+/// wildcards `*` and `?`. Files with expected answers are never included
+pub fn glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let (dir, name) = match pattern.rsplit_once('/') {
+        Some((dir, name)) => (if dir.is_empty() { "/" } else { dir }, name),
+        None => (".", pattern),
+    };
+    if dir.contains(['*', '?']) {
+        return Err(anyhow!(
+            "Wildcards are only supported in file names, not in {:?}",
+            dir
+        ));
+    }
+
+    let pattern: Vec<char> = name.chars().collect();
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read directory {:?}", dir))? {
+        let path = entry?.path();
+        let is_match = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| wildcard_match(&pattern, &name.chars().collect::<Vec<_>>()));
+        let is_sidecar = path.extension().is_some_and(|ext| ext == SIDECAR_EXTENSION);
+        if is_match && !is_sidecar && path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn wildcard_match(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, _) => name.is_empty(),
+        (Some('*'), _) => {
+            wildcard_match(&pattern[1..], name)
+                || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some('?'), Some(_)) => wildcard_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => wildcard_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// The file with expected answers for an input, like `alice.answers` for `alice.txt`. It uses the
+/// same format as the answers file
+pub fn sidecar_path(input: &Path) -> PathBuf {
+    input.with_extension(SIDECAR_EXTENSION)
+}
+
+/// Read a file that doesn't have to exist, like the answers or baseline files. Returns `None` if the
+/// file is missing
+pub fn read_optional(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn normalize_newlines(input: String) -> String {
+    if input.contains('\r') {
+        input.replace("\r\n", "\n")
+    } else {
+        input
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(Source::from_path("-".into()), Source::Stdin);
+        assert_eq!(
+            Source::from_path("data/day5.txt".into()),
+            Source::File("data/day5.txt".into()),
+        );
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        let matches = |pattern: &str, name: &str| {
+            let pattern: Vec<_> = pattern.chars().collect();
+            wildcard_match(&pattern, &name.chars().collect::<Vec<_>>())
+        };
+        assert!(matches("*", "alice.txt"));
+        assert!(matches("*.txt", "alice.txt"));
+        assert!(matches("a?ice*", "alice.txt"));
+        assert!(!matches("*.txt", "alice.answers"));
+        assert!(!matches("b*", "alice.txt"));
+        assert_eq!(
+            sidecar_path(Path::new("data/day5/alice.txt")),
+            Path::new("data/day5/alice.answers")
+        );
+    }
+
+    #[test]
+    fn test_read_optional() {
+        assert_eq!(
+            read_optional(Path::new("data/does-not-exist.txt")).unwrap(),
+            None
+        );
+        assert!(read_optional(Path::new("Cargo.toml")).unwrap().is_some());
+        assert!(read_optional(Path::new("data")).is_err());
+    }
+
+    #[test]
+    fn test_read_inline() {
+        let source = Source::Inline("1|2\r\n\r\n1,2\r\n".to_string());
+        assert_eq!(source.read().unwrap(), "1|2\n\n1,2\n");
+        assert_eq!(source.to_string(), "<example>");
+    }
+}
@@ -1,103 +1,705 @@
 #![deny(clippy::dbg_macro)]
 
-use anyhow::{anyhow, Context as _, Result};
-use clap::Parser;
-use std::fs;
-use std::path::PathBuf;
-use std::time::Instant;
+use anyhow::{anyhow, Error, Result};
+use clap::{Args, Parser, Subcommand};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use answer::Answer;
+use answers::{Answers, Verdict};
+use baseline::{Baselines, Comparison};
+use bench::{Budget, Phases, Timing};
+use input::Source;
+use report::{Format, Outcome, Record};
+use solution::{Entry, Solved};
 
 // Expose the test macro to the entire crate
 #[macro_use]
 mod utils;
 
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
+// Expose the registry macro to the entire crate
+#[macro_use]
+mod solution;
+
+mod alloc;
+mod answer;
+mod answers;
+mod baseline;
+mod bench;
+mod cancel;
+mod client;
+mod input;
+mod report;
+mod scaffold;
+mod submit;
+mod watch;
+
+solutions! {
+    day1::Day1,
+    day2::Day2,
+    day3::Day3,
+    day4::Day4,
+    day5::Day5,
+    day6::Day6,
+    day7::Day7,
+    day8::Day8,
+    day9::Day9,
+    day10::Day10,
+    day11::Day11,
+    day12::Day12,
+    day13::Day13,
+    day14::Day14,
+}
 
 #[derive(Debug, Parser)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Options {
-    /// The day to run the solution for (1-25)
-    day: usize,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The day to run the solution for (1-25), a range of days like `3-9` or `all`
+    #[arg(required_unless_present = "list")]
+    days: Option<Days>,
+
+    /// List all implemented days and exit
+    #[arg(long)]
+    list: bool,
 
-    /// The input data file. Will look for `data/day<num>.txt` by default
+    /// The input data file, or `-` to read from stdin. Will look for `data/day<num>.txt` by
+    /// default. Only valid when running a single day
     input: Option<PathBuf>,
+
+    /// Use the given text as the puzzle input, which is useful for examples. Only valid when
+    /// running a single day
+    #[arg(short, long, value_name = "TEXT", conflicts_with = "input")]
+    example: Option<String>,
+
+    /// Rerun the day whenever its input file changes. Only valid when running a single day with
+    /// input from a file. Changes to the solution itself require rebuilding the binary
+    #[arg(long, conflicts_with_all = ["example", "bench", "format", "compare", "save_baseline"])]
+    watch: bool,
+
+    /// A directory of example inputs to run as well when watching. Changes to any file in it
+    /// trigger a rerun
+    #[arg(long, value_name = "DIR", requires = "watch")]
+    watch_examples: Option<PathBuf>,
+
+    /// Run the day for every file in `data/day<num>/`, or every file matching the given pattern,
+    /// and show which ones got the expected answers. Expected answers for `name.txt` are read
+    /// from `name.answers` in the same format as the answers file. Only valid when running a
+    /// single day
+    #[arg(
+        long,
+        value_name = "GLOB",
+        conflicts_with_all = ["input", "example", "watch", "compare", "save_baseline"],
+    )]
+    inputs: Option<Option<String>>,
+
+    /// Give up on a day that hasn't finished after this many seconds. When benchmarking, the limit
+    /// applies to each run rather than the whole benchmark. Solutions that check for cancellation
+    /// stop right away, while others keep running in the background until the program exits
+    #[arg(long, value_name = "SECONDS", value_parser = bench::parse_seconds)]
+    timeout: Option<Duration>,
+
+    /// Run each solution repeatedly and report timing statistics instead of a single time
+    #[arg(long)]
+    bench: bool,
+
+    /// The time budget in seconds for benchmarking each day
+    #[arg(long, default_value = "3", value_name = "SECONDS", value_parser = bench::parse_seconds)]
+    bench_time: Duration,
+
+    /// The maximum number of measured runs when benchmarking each day
+    #[arg(long, default_value_t = 10_000, value_name = "N")]
+    bench_iterations: usize,
+
+    /// The output format
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Exit with an error if any answer doesn't match the expected answer
+    #[arg(long)]
+    verify: bool,
+
+    /// The file with expected answers for the real puzzle inputs
+    #[arg(long, default_value = answers::DEFAULT_PATH, value_name = "PATH")]
+    answers: PathBuf,
+
+    /// Benchmark and save the results as a baseline with the given label
+    #[arg(long, value_name = "LABEL", value_parser = baseline::parse_label)]
+    save_baseline: Option<String>,
+
+    /// Benchmark and compare the results to the baseline with the given label. Exits with an
+    /// error if any day regressed more than the threshold
+    #[arg(
+        long,
+        value_name = "LABEL",
+        value_parser = baseline::parse_label,
+        conflicts_with = "format"
+    )]
+    compare: Option<String>,
+
+    /// The slowdown in percent that counts as a regression when comparing to a baseline
+    #[arg(
+        long,
+        default_value = "10",
+        value_name = "PERCENT",
+        value_parser = baseline::parse_threshold
+    )]
+    threshold: f64,
+
+    /// The file where baselines are stored
+    #[arg(long, default_value = "target/baselines.txt", value_name = "PATH")]
+    baseline_file: PathBuf,
 }
 
-fn run<F: FnOnce(&str) -> Result<(A, Option<B>)>, A: ToString, B: ToString>(
-    f: F,
-    input: &str,
-) -> Result<()> {
-    let start = Instant::now();
-    let (a, b) = f(input)?;
-    let time = Instant::now().saturating_duration_since(start);
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Create the module for a new day from a template, register it and add an empty input file
+    New {
+        /// The day to create (1-25)
+        #[arg(value_parser = solution::parse_day)]
+        day: usize,
+
+        /// The title of the puzzle
+        #[arg(long, default_value = "TODO")]
+        title: String,
+    },
+
+    /// Download the puzzle input for a day to `data/day<num>.txt` unless it's already there
+    Fetch {
+        /// The day to download the input for (1-25)
+        #[arg(value_parser = solution::parse_day)]
+        day: usize,
+
+        #[command(flatten)]
+        remote: Remote,
+    },
+
+    /// Solve a part of a day and submit the answer. Answers that are known to be wrong from earlier
+    /// submissions are not submitted again
+    Submit {
+        /// The day to submit the answer for (1-25)
+        #[arg(value_parser = solution::parse_day)]
+        day: usize,
 
-    println!("A: {}", pad_newlines(a.to_string()));
-    if let Some(b) = b {
-        println!("B: {}", pad_newlines(b.to_string()));
+        /// The part to submit the answer for (a or b)
+        #[arg(value_parser = submit::parse_part)]
+        part: char,
+
+        /// The file where every submission is recorded
+        #[arg(long, default_value = submit::DEFAULT_HISTORY_PATH, value_name = "PATH")]
+        history: PathBuf,
+
+        #[command(flatten)]
+        remote: Remote,
+    },
+}
+
+/// How to reach the Advent of Code site
+#[derive(Debug, Args)]
+struct Remote {
+    /// The site to send requests to
+    #[arg(long, default_value = client::DEFAULT_BASE_URL, value_name = "URL")]
+    base_url: String,
+
+    /// The file with the session token, used when the AOC_SESSION environment variable isn't set
+    #[arg(long, default_value = client::DEFAULT_SESSION_FILE, value_name = "PATH")]
+    session_file: PathBuf,
+
+    /// The minimum time in seconds between requests to the site
+    #[arg(long, default_value = "5", value_name = "SECONDS", value_parser = bench::parse_seconds)]
+    min_interval: Duration,
+}
+
+impl Remote {
+    fn client(&self) -> Result<client::Client<client::Curl>> {
+        Ok(client::Client {
+            http: client::Curl,
+            base_url: self.base_url.clone(),
+            session: client::session(&self.session_file)?,
+            throttle: client::Throttle {
+                stamp: client::DEFAULT_STAMP_FILE.into(),
+                interval: self.min_interval,
+            },
+        })
     }
-    println!();
+}
 
-    let ns = time.as_nanos();
-    if ns < 10000 {
-        println!("Time: {ns} ns");
-    } else if ns < 1_000_000 {
-        println!("Time: {} µs", (ns + 500) / 1_000);
-    } else if ns < 1_000_000_000 {
-        println!("Time: {} ms", (ns + 500_000) / 1_000_000);
-    } else {
-        println!("Time: {:.3} s", time.as_secs_f64());
+impl Options {
+    fn budget(&self) -> Option<Budget> {
+        let is_bench = self.bench || self.save_baseline.is_some() || self.compare.is_some();
+        is_bench.then_some(Budget {
+            time: self.bench_time,
+            iterations: self.bench_iterations,
+        })
     }
+}
 
-    Ok(())
+#[derive(Debug, Clone)]
+enum Days {
+    Single(usize),
+    Range(RangeInclusive<usize>),
+    All,
+}
+
+impl FromStr for Days {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "all" {
+            return Ok(Self::All);
+        }
+        if let Some((start, end)) = s.split_once('-') {
+            let (start, end) = (solution::parse_day(start)?, solution::parse_day(end)?);
+            if start > end {
+                return Err(anyhow!("Day range {s:?} is empty"));
+            }
+            return Ok(Self::Range(start..=end));
+        }
+        Ok(Self::Single(solution::parse_day(s)?))
+    }
 }
 
-fn pad_newlines(answer: String) -> String {
-    answer.lines().collect::<Vec<_>>().join("\n   ")
+fn solution(day: usize) -> Result<&'static Entry> {
+    SOLUTIONS
+        .iter()
+        .find(|solution| solution.day == day)
+        .ok_or_else(|| anyhow!("No implementation for day {} yet", day))
+}
+
+/// Time the solution, where `run` solves the puzzle once. It's called several times when
+/// benchmarking
+fn execute(mut run: impl FnMut() -> Result<Solved>, budget: Option<Budget>) -> Result<Outcome> {
+    // Benchmarks run the solution many times, so memory is measured in a separate run
+    let mut memory = None;
+    let (solved, phases, timing) = match budget {
+        Some(budget) => {
+            if alloc::ENABLED {
+                let (solved, measured) = alloc::measure(&mut run);
+                solved?;
+                memory = measured;
+            }
+
+            // Only the measured runs count, so the phases describe the same runs as the stats
+            let (measured, stats) = bench::bench(&mut run, budget)?;
+            let phase_samples: Vec<_> = measured.iter().map(|solved| solved.phases).collect();
+            let phases = Phases::median(&phase_samples).expect("There is always a measured run");
+            let solved = measured
+                .into_iter()
+                .next()
+                .expect("There is always a measured run");
+            (solved, phases, Timing::Bench(stats))
+        }
+        None => {
+            let ((solved, time), measured) = alloc::measure(|| bench::time(run));
+            let solved = solved?;
+            memory = measured;
+            let phases = solved.phases;
+            (solved, phases, Timing::Once(time))
+        }
+    };
+    Ok(Outcome {
+        a: solved.a,
+        b: solved.b,
+        phases,
+        timing,
+        memory,
+    })
+}
+
+/// Call the function and turn a panic into an error, so a broken day shows up as a failed row
+/// instead of ending the program
+fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str));
+        match message {
+            Some(message) => Err(anyhow!("The solution panicked: {}", message)),
+            None => Err(anyhow!("The solution panicked")),
+        }
+    })
+}
+
+/// What the thread running a solution has to say about its progress
+enum Progress {
+    /// The solution is about to solve the puzzle once more
+    Started,
+    Finished(Box<Result<Outcome>>),
+}
+
+/// Run the solution in a separate thread and give up if a single run doesn't finish in time, so
+/// benchmarks may take longer than the timeout in total. The solution is asked to stop through its
+/// cancellation token when the time is up. The thread isn't waited for, since a solution that never
+/// checks the token would block the next day
+fn execute_with_timeout(
+    solution: &'static Entry,
+    input: String,
+    budget: Option<Budget>,
+    timeout: Duration,
+) -> Result<Outcome> {
+    let token = cancel::Token::default();
+    let (sender, receiver) = mpsc::channel();
+    let thread_token = token.clone();
+    thread::spawn(move || {
+        let run = || {
+            let _ = sender.send(Progress::Started);
+            solution.run(&input)
+        };
+        let outcome = cancel::with_token(thread_token, || catch_panic(|| execute(run, budget)));
+        let _ = sender.send(Progress::Finished(Box::new(outcome)));
+    });
+
+    loop {
+        match receiver.recv_timeout(timeout) {
+            Ok(Progress::Started) => {}
+            Ok(Progress::Finished(outcome)) => return *outcome,
+            Err(RecvTimeoutError::Timeout) => {
+                token.cancel();
+                return Err(cancel::TimedOut(timeout).into());
+            }
+            Err(RecvTimeoutError::Disconnected) => return Err(anyhow!("The solution panicked")),
+        }
+    }
+}
+
+/// Run the solution for the given day. Any failure, including a missing input file, is captured
+/// in the record
+fn run(
+    day: usize,
+    input: Option<Source>,
+    budget: Option<Budget>,
+    timeout: Option<Duration>,
+) -> Record {
+    let input = input.unwrap_or_else(|| Source::for_day(day));
+    let outcome = solution(day).and_then(|solution| {
+        let input = input.read()?;
+        match timeout {
+            Some(timeout) => execute_with_timeout(solution, input, budget, timeout),
+            None => catch_panic(|| execute(|| solution.run(&input), budget)),
+        }
+    });
+    Record {
+        day,
+        input,
+        outcome,
+        expected_a: None,
+        expected_b: None,
+    }
 }
 
 fn main() -> Result<()> {
     let opts = Options::parse();
+    if let Some(command) = &opts.command {
+        return run_command(command);
+    }
+    if opts.list {
+        for solution in SOLUTIONS {
+            println!("{:>2}  {}", solution.day, solution.title);
+        }
+        return Ok(());
+    }
 
-    #[allow(
-        overlapping_range_endpoints,
-        unreachable_patterns,
-        clippy::match_overlapping_arm
-    )]
-    let solution = match opts.day {
-        1 => day1::main,
-        2 => day2::main,
-        3 => day3::main,
-        4 => day4::main,
-        5 => day5::main,
-        6 => day6::main,
-        7 => day7::main,
-        8 => day8::main,
-        9 => day9::main,
-        10 => day10::main,
-        11 => day11::main,
-        12 => day12::main,
-        13 => day13::main,
-        14 => day14::main,
-        day @ 1..=25 => return Err(anyhow!("No implementation for day {} yet", day)),
-        day => return Err(anyhow!("Day {} is not a valid day for advent of code", day)),
+    // Clap ensures that days are given unless listing
+    let days = opts.days.clone().unwrap();
+    let budget = opts.budget();
+    let is_single_day = matches!(days, Days::Single(_));
+
+    // Check that the baseline exists before spending time on benchmarking
+    if let Some(label) = &opts.compare {
+        if !Baselines::load(&opts.baseline_file)?.has_label(label) {
+            return Err(anyhow!(
+                "No baseline named {:?} in {:?}",
+                label,
+                opts.baseline_file
+            ));
+        }
+    }
+
+    let mut records = match days {
+        Days::Single(day) => {
+            let input = match (&opts.input, &opts.example) {
+                (Some(path), _) => Some(Source::from_path(path.clone())),
+                (None, Some(example)) => Some(Source::Inline(example.clone())),
+                (None, None) => None,
+            };
+            if opts.watch {
+                return watch_day(day, input, &opts);
+            }
+            if let Some(pattern) = &opts.inputs {
+                run_inputs(day, pattern.as_deref(), budget, opts.timeout)?
+            } else {
+                vec![run(day, input, budget, opts.timeout)]
+            }
+        }
+        days => {
+            if opts.watch {
+                return Err(anyhow!("Only a single day can be watched"));
+            }
+            if opts.inputs.is_some() {
+                return Err(anyhow!(
+                    "Multiple inputs can only be given when running a single day"
+                ));
+            }
+            if opts.input.is_some() || opts.example.is_some() {
+                return Err(anyhow!(
+                    "An input can only be given when running a single day"
+                ));
+            }
+            let days: Vec<_> = match days {
+                Days::Range(range) => range.collect(),
+                _ => SOLUTIONS.iter().map(|solution| solution.day).collect(),
+            };
+            days.into_iter()
+                .map(|day| run(day, None, budget, opts.timeout))
+                .collect()
+        }
+    };
+
+    // Expected answers are only known for the real puzzle inputs
+    let answers = Answers::load(&opts.answers)?;
+    for record in records.iter_mut() {
+        if record.input == Source::for_day(record.day) {
+            record.set_expected(&answers);
+        }
+    }
+
+    if let Some(label) = &opts.save_baseline {
+        let mut baselines = Baselines::load(&opts.baseline_file)?;
+        for record in records.iter() {
+            if let Ok(outcome) = &record.outcome {
+                baselines.insert(label, record.day, outcome.timing.typical());
+            }
+        }
+        baselines.save(&opts.baseline_file)?;
+    }
+
+    if let Some(label) = &opts.compare {
+        return compare(&records, label, &opts);
+    }
+
+    match opts.format {
+        Format::Text if opts.inputs.is_some() => report::print_matrix(&records),
+        Format::Text if is_single_day => {
+            let record = &records[0];
+            let Ok(outcome) = &record.outcome else {
+                return Err(records.remove(0).outcome.unwrap_err());
+            };
+            report::print_outcome(
+                outcome,
+                record.expected_a.as_ref(),
+                record.expected_b.as_ref(),
+            );
+        }
+        Format::Text => report::print_table(&records, budget.is_some()),
+        Format::Json => println!("{}", report::json(&records)),
+        Format::Csv => print!("{}", report::csv(&records)),
+    }
+
+    if opts.verify {
+        verify(&records)?;
+    }
+    Ok(())
+}
+
+fn run_command(command: &Command) -> Result<()> {
+    match command {
+        Command::New { day, title } => {
+            for path in scaffold::new_day(*day, title, Path::new("."))? {
+                println!("Wrote {}", path.display());
+            }
+        }
+        Command::Fetch { day, remote } => {
+            let path = input::default_path(*day);
+            if client::fetch(&path, || remote.client()?.input(*day))? {
+                println!("Wrote {}", path.display());
+            } else {
+                println!("{} already exists", path.display());
+            }
+        }
+        Command::Submit {
+            day,
+            part,
+            history,
+            remote,
+        } => submit_answer(*day, *part, history, remote)?,
+    }
+    Ok(())
+}
+
+fn submit_answer(day: usize, part: char, history_path: &Path, remote: &Remote) -> Result<()> {
+    // Only the submitted part has to be solved, since part A is submitted before part B exists
+    let solution = solution(day)?;
+    let input = Source::for_day(day).read()?;
+    let answer = catch_panic(|| solution.run_part(&input, part))?
+        .ok_or_else(|| anyhow!("Day {} has no part {} answer yet", day, part))?;
+    if let Answer::Multiline(_) = answer {
+        return Err(anyhow!(
+            "Answers spanning several lines must be read and submitted by hand"
+        ));
+    }
+
+    let history = submit::History::load(history_path)?;
+    if let Some(reason) = history.rejection(day, part, &answer, SystemTime::now()) {
+        return Err(anyhow!("Refusing to submit: {}", reason));
+    }
+
+    let page = remote.client()?.submit(day, part, &answer.to_string())?;
+    let feedback = submit::Feedback::parse(&page, SystemTime::now());
+    let attempt = submit::Attempt {
+        day,
+        part,
+        feedback: feedback.clone(),
+        answer: answer.to_string(),
+    };
+    submit::History::append(history_path, &attempt)?;
+
+    println!("{} {}: {}", day, part, answer);
+    if feedback != submit::Feedback::Correct {
+        return Err(anyhow!("{}", feedback));
+    }
+    println!("{}", feedback);
+    Ok(())
+}
+
+/// Run the day for each input matching the pattern, or all extra inputs for the day if there is
+/// no pattern. Expected answers come from the file next to each input
+fn run_inputs(
+    day: usize,
+    pattern: Option<&str>,
+    budget: Option<Budget>,
+    timeout: Option<Duration>,
+) -> Result<Vec<Record>> {
+    let paths = match pattern {
+        Some(pattern) => input::glob(pattern)?,
+        None => input::day_inputs(day)?,
     };
+    if paths.is_empty() {
+        return Err(anyhow!("No inputs found for day {}", day));
+    }
+
+    let mut records = Vec::new();
+    for path in paths {
+        let answers = Answers::load(input::sidecar_path(&path))?;
+        let mut record = run(day, Some(Source::File(path)), budget, timeout);
+        record.set_expected(&answers);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Run the day and its examples every time one of their files change, showing how the answers
+/// changed since the previous run. Runs until interrupted
+fn watch_day(day: usize, input: Option<Source>, opts: &Options) -> Result<()> {
+    let input = input.unwrap_or_else(|| Source::for_day(day));
+    let Source::File(path) = &input else {
+        return Err(anyhow!("Only input files can be watched"));
+    };
+    let mut watcher = watch::Watcher::new(vec![path.clone()], opts.watch_examples.clone());
+    let mut previous = HashMap::new();
+    loop {
+        print!("{}", watch::CLEAR_SCREEN);
+        let examples = watcher.dir_files().into_iter().map(Source::File);
+        for source in std::iter::once(input.clone()).chain(examples) {
+            println!("== {} ==", source);
+            let mut record = run(day, Some(source.clone()), None, opts.timeout);
+
+            // Load the answers every time since they're likely to be filled in while watching
+            if source == Source::for_day(day) {
+                record.set_expected(&Answers::load(&opts.answers)?);
+            }
 
-    let input_path = opts
-        .input
-        .unwrap_or_else(|| format!("data/day{}.txt", opts.day).into());
-    let input = fs::read_to_string(&input_path)
-        .with_context(|| format!("Failed to open input file {:?}", input_path))?;
-    run(solution, &input)
+            match record.outcome {
+                Ok(outcome) => {
+                    report::print_outcome(
+                        &outcome,
+                        record.expected_a.as_ref(),
+                        record.expected_b.as_ref(),
+                    );
+                    let answers = (outcome.a, outcome.b);
+                    let changes = watch::diff(previous.get(&source.to_string()), &answers);
+                    if !changes.is_empty() {
+                        println!("\nChanges since the previous run:");
+                        for change in changes {
+                            println!("  {}", change);
+                        }
+                    }
+                    previous.insert(source.to_string(), answers);
+                }
+                Err(e) => println!("Failed: {:#}", e),
+            }
+            println!();
+        }
+        println!("Watching for changes, press Ctrl-C to stop");
+        watcher.wait(Duration::from_millis(500));
+    }
+}
+
+/// Check that every day with known answers ran successfully and got the right answers
+fn verify(records: &[Record]) -> Result<()> {
+    let mut num_failures = 0;
+    for record in records {
+        if record.expected_a.is_none() && record.expected_b.is_none() {
+            continue;
+        }
+        match &record.outcome {
+            Ok(_) => {
+                let [a, b] = record.verdicts();
+                num_failures += usize::from(a == Some(Verdict::Wrong));
+                num_failures += usize::from(b == Some(Verdict::Wrong));
+            }
+            Err(e) => {
+                eprintln!("Day {} failed: {:#}", record.day, e);
+                num_failures += 1;
+            }
+        }
+    }
+
+    if num_failures > 0 {
+        return Err(anyhow!(
+            "{} answer(s) did not match the expected answers",
+            num_failures
+        ));
+    }
+    Ok(())
+}
+
+fn compare(records: &[Record], label: &str, opts: &Options) -> Result<()> {
+    let baselines = Baselines::load(&opts.baseline_file)?;
+    let mut comparisons = Vec::new();
+    for record in records {
+        match &record.outcome {
+            Ok(outcome) => comparisons.push(Comparison {
+                day: record.day,
+                baseline: baselines.get(label, record.day),
+                current: outcome.timing.typical(),
+            }),
+            Err(e) => eprintln!("Day {} failed: {:#}", record.day, e),
+        }
+    }
+    baseline::print_comparisons(label, &comparisons, opts.threshold);
+
+    let num_regressions = comparisons
+        .iter()
+        .filter(|cmp| cmp.is_regression(opts.threshold))
+        .count();
+    if num_regressions > 0 {
+        return Err(anyhow!(
+            "{} day(s) regressed by more than {}%",
+            num_regressions,
+            opts.threshold
+        ));
+    }
+    if records.iter().any(|record| record.outcome.is_err()) {
+        return Err(anyhow!("Some days failed to run"));
+    }
+    Ok(())
 }
@@ -0,0 +1,563 @@
+use anyhow::{Error, Result};
+use clap::ValueEnum;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use crate::alloc::{self, Memory};
+use crate::answer::Answer;
+use crate::answers::{Answers, Verdict};
+use crate::bench::{Phases, Timing};
+use crate::cancel::TimedOut;
+use crate::input::Source;
+
+/// How results are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human readable output
+    Text,
+
+    /// A JSON array with one object per day
+    Json,
+
+    /// A CSV file with a header and one row per day
+    Csv,
+}
+
+/// The answers and timing from a successful run
+#[derive(Debug)]
+pub struct Outcome {
+    pub a: Answer,
+    pub b: Option<Answer>,
+
+    /// The time spent in each phase. When benchmarking this is the median of each phase
+    pub phases: Phases,
+    pub timing: Timing,
+
+    /// Only known when allocations are counted
+    pub memory: Option<Memory>,
+}
+
+/// The result of running the solution for a single day
+#[derive(Debug)]
+pub struct Record {
+    pub day: usize,
+    pub input: Source,
+    pub outcome: Result<Outcome>,
+
+    /// The known correct answers, which are only set for the real puzzle input
+    pub expected_a: Option<Answer>,
+    pub expected_b: Option<Answer>,
+}
+
+impl Record {
+    /// Use the answers for the day of the record as the expected answers
+    pub fn set_expected(&mut self, answers: &Answers) {
+        self.expected_a = answers.a(self.day).map(Answer::parse);
+        self.expected_b = answers.b(self.day).map(Answer::parse);
+    }
+
+    /// Check the answers for part A and B against the expected answers
+    pub fn verdicts(&self) -> [Option<Verdict>; 2] {
+        let outcome = self.outcome.as_ref().ok();
+        [
+            Verdict::of(self.expected_a.as_ref(), outcome.map(|o| &o.a)),
+            Verdict::of(self.expected_b.as_ref(), outcome.and_then(|o| o.b.as_ref())),
+        ]
+    }
+}
+
+/// Print the answers and time for a single day. Answers are marked as correct or wrong when the
+/// expected answer is known
+pub fn print_outcome(outcome: &Outcome, expected_a: Option<&Answer>, expected_b: Option<&Answer>) {
+    let with_verdict = |answer: &Answer, expected: Option<&Answer>| {
+        let padded = pad_newlines(&answer.to_string());
+        match (Verdict::of(expected, Some(answer)), expected) {
+            (Some(Verdict::Wrong), Some(expected)) => format!("{padded} ✗ (expected {expected})"),
+            (Some(Verdict::Correct), _) => format!("{padded} ✓"),
+            _ => padded,
+        }
+    };
+    println!("A: {}", with_verdict(&outcome.a, expected_a));
+    if let Some(b) = &outcome.b {
+        println!("B: {}", with_verdict(b, expected_b));
+    }
+    println!();
+
+    let phases = &outcome.phases;
+    println!(
+        "Parse:   {}\nPart A:  {}\nPart B:  {}",
+        format_duration(phases.parse),
+        format_duration(phases.part_a),
+        format_duration(phases.part_b),
+    );
+    match outcome.timing {
+        Timing::Once(time) => println!("Time:    {}", format_duration(time)),
+        Timing::Bench(stats) => {
+            println!("Runs:    {}", stats.runs);
+            println!("Min:     {}", format_duration(stats.min));
+            println!("Median:  {}", format_duration(stats.median));
+            println!("Mean:    {}", format_duration(stats.mean));
+            println!("Std dev: {}", format_duration(stats.std_dev));
+            println!("P95:     {}", format_duration(stats.p95));
+        }
+    }
+    if let Some(memory) = outcome.memory {
+        println!("Peak:    {}", format_bytes(memory.peak));
+        println!("Allocs:  {}", memory.allocations);
+        println!("Total:   {}", format_bytes(memory.allocated));
+    }
+}
+
+/// Print a table with one row per day, followed by the total time. Failed days are shown with
+/// their error instead of answers
+pub fn print_table(records: &[Record], is_bench: bool) {
+    let mut header = vec!["Day", "A", "B", "Parse", "Part A", "Part B"];
+    if is_bench {
+        header.extend(["Runs", "Min", "Median", "Mean", "Std dev", "P95"]);
+    } else {
+        header.push("Time");
+    }
+    if alloc::ENABLED {
+        header.extend(["Peak", "Allocs", "Total"]);
+    }
+
+    let mut total = Duration::ZERO;
+    let rows: Vec<_> = records
+        .iter()
+        .map(|record| {
+            let [verdict_a, verdict_b] = record.verdicts();
+            record.outcome.as_ref().map(|outcome| {
+                total += outcome.timing.typical();
+
+                let mut cells = vec![
+                    with_mark(Some(&outcome.a), verdict_a),
+                    with_mark(outcome.b.as_ref(), verdict_b),
+                    format_duration(outcome.phases.parse),
+                    format_duration(outcome.phases.part_a),
+                    format_duration(outcome.phases.part_b),
+                ];
+                match outcome.timing {
+                    Timing::Once(time) => cells.push(format_duration(time)),
+                    Timing::Bench(stats) => cells.extend([
+                        stats.runs.to_string(),
+                        format_duration(stats.min),
+                        format_duration(stats.median),
+                        format_duration(stats.mean),
+                        format_duration(stats.std_dev),
+                        format_duration(stats.p95),
+                    ]),
+                }
+                if let Some(memory) = outcome.memory {
+                    cells.extend([
+                        format_bytes(memory.peak),
+                        memory.allocations.to_string(),
+                        format_bytes(memory.allocated),
+                    ]);
+                }
+                cells
+            })
+        })
+        .collect();
+
+    let mut widths: Vec<_> = header.iter().map(|h| h.chars().count()).collect();
+    for (record, row) in records.iter().zip(&rows) {
+        widths[0] = widths[0].max(record.day.to_string().len());
+        for (width, cell) in widths[1..].iter_mut().zip(row.iter().flatten()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    // Answers are left aligned while the day and all timing columns are right aligned
+    let format_row = |day: &str, cells: &[&str]| {
+        let mut line = format!("{:>w$}", day, w = widths[0]);
+        for (i, (cell, width)) in cells.iter().zip(&widths[1..]).enumerate() {
+            if i < 2 {
+                line.push_str(&format!("  {:<width$}", cell));
+            } else {
+                line.push_str(&format!("  {:>width$}", cell));
+            }
+        }
+        line
+    };
+
+    println!("{}", format_row(header[0], &header[1..]));
+    for (record, row) in records.iter().zip(rows) {
+        let day = record.day.to_string();
+        match row {
+            Ok(cells) => {
+                let cells: Vec<_> = cells.iter().map(String::as_str).collect();
+                println!("{}", format_row(&day, &cells));
+            }
+            // Errors span the answer columns as they tend to be much longer than the answers
+            Err(e) => println!("{:>w$}  {}", day, describe_error(e), w = widths[0]),
+        }
+    }
+    println!();
+    println!("Total time: {}", format_duration(total));
+}
+
+/// Show the answer in a table cell, marked as correct or wrong when the expected answer is known.
+/// Answers spanning several lines are replaced by their number of lines to keep the table intact
+fn with_mark(answer: Option<&Answer>, verdict: Option<Verdict>) -> String {
+    let answer = match answer {
+        Some(Answer::Multiline(s)) => format!("<{} lines>", s.lines().count()),
+        Some(answer) => answer.to_string(),
+        None => String::new(),
+    };
+    match verdict {
+        Some(verdict) => format!("{} {}", answer, verdict.mark()),
+        None => answer,
+    }
+}
+
+/// Print one row per input of the same day with whether it passed, followed by a summary. An input
+/// passes when every answer with a known expected answer is correct
+pub fn print_matrix(records: &[Record]) {
+    let header = ["Input", "A", "B", "Time", "Result"];
+    let rows: Vec<Result<[String; 5], &Error>> = records
+        .iter()
+        .map(|record| {
+            let outcome = record.outcome.as_ref()?;
+            let verdicts = record.verdicts();
+            let result = if verdicts.contains(&Some(Verdict::Wrong)) {
+                "fail"
+            } else if verdicts.contains(&Some(Verdict::Correct)) {
+                "pass"
+            } else {
+                "unknown"
+            };
+            Ok([
+                record.input.to_string(),
+                with_mark(Some(&outcome.a), verdicts[0]),
+                with_mark(outcome.b.as_ref(), verdicts[1]),
+                format_duration(outcome.timing.typical()),
+                result.to_string(),
+            ])
+        })
+        .collect();
+
+    let mut widths = header.map(|h| h.chars().count());
+    for (record, row) in records.iter().zip(&rows) {
+        widths[0] = widths[0].max(record.input.to_string().chars().count());
+        for (width, cell) in widths.iter_mut().zip(row.iter().flatten()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    // Answers are left aligned while the time is right aligned
+    let format_row = |cells: [&str; 5]| {
+        let [input, a, b, time, result] = cells;
+        let line = format!(
+            "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {}",
+            input,
+            a,
+            b,
+            time,
+            result,
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+        );
+        line.trim_end().to_string()
+    };
+
+    println!("{}", format_row(header));
+    let (mut passed, mut failed, mut unknown) = (0, 0, 0);
+    for (record, row) in records.iter().zip(&rows) {
+        match row {
+            Ok(cells) => {
+                match cells[4].as_str() {
+                    "pass" => passed += 1,
+                    "fail" => failed += 1,
+                    _ => unknown += 1,
+                }
+                println!("{}", format_row(cells.each_ref().map(String::as_str)));
+            }
+            // Errors span the remaining columns as they tend to be much longer than the answers
+            Err(e) => {
+                failed += 1;
+                let input = record.input.to_string();
+                println!("{:<w$}  {}", input, describe_error(e), w = widths[0]);
+            }
+        }
+    }
+    println!();
+    println!(
+        "{} passed, {} failed, {} without expected answers",
+        passed, failed, unknown
+    );
+}
+
+/// A value in the JSON and CSV output, where `None` is a missing value
+enum Value {
+    /// Quoted in JSON
+    Text(Option<String>),
+
+    /// Numbers and booleans, which are written as they are
+    Plain(Option<String>),
+}
+
+/// The names of the columns in the JSON and CSV output
+const COLUMNS: [&str; 20] = [
+    "day",
+    "answer_a",
+    "answer_b",
+    "correct_a",
+    "correct_b",
+    "duration_ns",
+    "parse_ns",
+    "part_a_ns",
+    "part_b_ns",
+    "input",
+    "error",
+    "runs",
+    "min_ns",
+    "median_ns",
+    "mean_ns",
+    "std_dev_ns",
+    "p95_ns",
+    "peak_bytes",
+    "allocations",
+    "allocated_bytes",
+];
+
+/// The value of every column in `COLUMNS` for the record, in the same order
+fn values(record: &Record) -> [Value; COLUMNS.len()] {
+    let outcome = record.outcome.as_ref().ok();
+    let stats = outcome.and_then(|o| match o.timing {
+        Timing::Bench(stats) => Some(stats),
+        Timing::Once(_) => None,
+    });
+    let memory = outcome.and_then(|o| o.memory);
+    let [verdict_a, verdict_b] = record.verdicts();
+
+    let count = |n: Option<usize>| Value::Plain(n.map(|n| n.to_string()));
+    let ns = |d: Option<Duration>| Value::Plain(d.map(|d| d.as_nanos().to_string()));
+    let correct = |v: Option<Verdict>| Value::Plain(v.map(|v| (v == Verdict::Correct).to_string()));
+    [
+        Value::Plain(Some(record.day.to_string())),
+        Value::Text(outcome.map(|o| o.a.to_string())),
+        Value::Text(outcome.and_then(|o| o.b.as_ref()).map(Answer::to_string)),
+        correct(verdict_a),
+        correct(verdict_b),
+        ns(outcome.map(|o| o.timing.typical())),
+        ns(outcome.map(|o| o.phases.parse)),
+        ns(outcome.map(|o| o.phases.part_a)),
+        ns(outcome.map(|o| o.phases.part_b)),
+        Value::Text(Some(record.input.to_string())),
+        Value::Text(record.outcome.as_ref().err().map(|e| format!("{e:#}"))),
+        count(stats.map(|s| s.runs)),
+        ns(stats.map(|s| s.min)),
+        ns(stats.map(|s| s.median)),
+        ns(stats.map(|s| s.mean)),
+        ns(stats.map(|s| s.std_dev)),
+        ns(stats.map(|s| s.p95)),
+        count(memory.map(|m| m.peak)),
+        count(memory.map(|m| m.allocations)),
+        count(memory.map(|m| m.allocated)),
+    ]
+}
+
+/// Render the records as a JSON array. Every object has the same keys in the same order, and
+/// missing values are `null`, which makes the output easy to diff. Answers are always written as
+/// strings, since many JSON parsers lose precision on large numbers
+pub fn json(records: &[Record]) -> String {
+    let mut out = String::from("[\n");
+    for (i, record) in records.iter().enumerate() {
+        out.push_str("  {");
+        for (j, (key, value)) in COLUMNS.iter().zip(values(record)).enumerate() {
+            if j > 0 {
+                out.push_str(", ");
+            }
+            let value = match value {
+                Value::Text(Some(s)) => json_str(&s),
+                Value::Plain(Some(s)) => s,
+                Value::Text(None) | Value::Plain(None) => "null".to_string(),
+            };
+            write!(out, "\"{key}\": {value}").unwrap();
+        }
+        out.push('}');
+        if i + 1 < records.len() {
+            out.push(',');
+        }
+        out.push('\n');
+    }
+    out.push(']');
+    out
+}
+
+/// Render the records as CSV with a header row. The columns are always the same, and values
+/// that don't apply are left empty
+pub fn csv(records: &[Record]) -> String {
+    let mut out = COLUMNS.join(",") + "\n";
+    for record in records {
+        let line: Vec<_> = values(record)
+            .into_iter()
+            .map(|value| match value {
+                Value::Text(s) | Value::Plain(s) => csv_field(&s.unwrap_or_default()),
+            })
+            .collect();
+        out.push_str(&line.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn format_duration(time: Duration) -> String {
+    let ns = time.as_nanos();
+    if ns < 10000 {
+        format!("{ns} ns")
+    } else if ns < 1_000_000 {
+        format!("{} µs", (ns + 500) / 1_000)
+    } else if ns < 1_000_000_000 {
+        format!("{} ms", (ns + 500_000) / 1_000_000)
+    } else {
+        format!("{:.3} s", time.as_secs_f64())
+    }
+}
+
+/// Timeouts are expected when trying out slow solutions, so they aren't presented as errors
+fn describe_error(e: &Error) -> String {
+    match e.downcast_ref::<TimedOut>() {
+        Some(timed_out) => timed_out.to_string(),
+        None => format!("Error: {:#}", e),
+    }
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    if bytes < 10 * 1024 {
+        format!("{bytes} B")
+    } else if bytes < 10 * 1024 * 1024 {
+        format!("{} KiB", (bytes + 512) / 1024)
+    } else {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+pub fn pad_newlines(answer: &str) -> String {
+    answer.lines().collect::<Vec<_>>().join("\n   ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_with_mark() {
+        let answer = Answer::from(42usize);
+        assert_eq!(with_mark(Some(&answer), None), "42");
+        assert_eq!(with_mark(Some(&answer), Some(Verdict::Correct)), "42 ✓");
+        assert_eq!(with_mark(None, None), "");
+
+        let multiline = Answer::Multiline("#.\n.#\n##".to_string());
+        assert_eq!(
+            with_mark(Some(&multiline), Some(Verdict::Wrong)),
+            "<3 lines> ✗"
+        );
+    }
+    use anyhow::anyhow;
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                day: 1,
+                input: Source::for_day(1),
+                outcome: Ok(Outcome {
+                    a: Answer::from(11usize),
+                    b: Some(Answer::from("line 1\nline \"2\"")),
+                    phases: Phases {
+                        parse: Duration::from_nanos(200),
+                        part_a: Duration::from_nanos(300),
+                        part_b: Duration::from_nanos(700),
+                    },
+                    timing: Timing::Once(Duration::from_nanos(1234)),
+                    memory: Some(Memory {
+                        peak: 2048,
+                        allocations: 3,
+                        allocated: 4096,
+                    }),
+                }),
+                expected_a: Some(Answer::from(11usize)),
+                expected_b: None,
+            },
+            Record {
+                day: 2,
+                input: Source::for_day(2),
+                outcome: Err(anyhow!("Failed, to parse")),
+                expected_a: Some(Answer::from(31usize)),
+                expected_b: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            json(&records()),
+            concat!(
+                "[\n",
+                r#"  {"day": 1, "answer_a": "11", "answer_b": "line 1\nline \"2\"", "correct_a": true, "correct_b": null, "duration_ns": 1234, "parse_ns": 200, "part_a_ns": 300, "part_b_ns": 700, "input": "data/day1.txt", "error": null, "runs": null, "min_ns": null, "median_ns": null, "mean_ns": null, "std_dev_ns": null, "p95_ns": null, "peak_bytes": 2048, "allocations": 3, "allocated_bytes": 4096},"#,
+                "\n",
+                r#"  {"day": 2, "answer_a": null, "answer_b": null, "correct_a": null, "correct_b": null, "duration_ns": null, "parse_ns": null, "part_a_ns": null, "part_b_ns": null, "input": "data/day2.txt", "error": "Failed, to parse", "runs": null, "min_ns": null, "median_ns": null, "mean_ns": null, "std_dev_ns": null, "p95_ns": null, "peak_bytes": null, "allocations": null, "allocated_bytes": null}"#,
+                "\n]",
+            )
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = csv(&records());
+        let (header, rows) = csv.split_once('\n').unwrap();
+        assert_eq!(header.split(',').count(), 20);
+        assert_eq!(
+            rows,
+            concat!(
+                "1,11,\"line 1\nline \"\"2\"\"\",true,,1234,200,300,700,data/day1.txt,,,,,,,,2048,3,4096\n",
+                "2,,,,,,,,,data/day2.txt,\"Failed, to parse\",,,,,,,,,\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(9999)), "9999 ns");
+        assert_eq!(format_duration(Duration::from_nanos(10_499)), "10 µs");
+        assert_eq!(format_duration(Duration::from_micros(1_500)), "2 ms");
+        assert_eq!(format_duration(Duration::from_millis(1_500)), "1.500 s");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(10_239), "10239 B");
+        assert_eq!(format_bytes(10_240), "10 KiB");
+        assert_eq!(format_bytes(1536 * 1024), "1536 KiB");
+        assert_eq!(format_bytes(15 * 1024 * 1024 + 1024 * 512), "15.5 MiB");
+    }
+}
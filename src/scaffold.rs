@@ -0,0 +1,144 @@
+use anyhow::{anyhow, Context as _, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The module for a new day. `{day}` and `{title}` are replaced when rendering
+const TEMPLATE: &str = r#"use anyhow::{anyhow, Result};
+
+use crate::solution::Solution;
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    const DAY: usize = {day};
+    const TITLE: &'static str = {title};
+
+    type Input = Vec<String>;
+    type A = usize;
+    type B = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.lines().map(String::from).collect())
+    }
+
+    fn part_a(_input: &Self::Input) -> Result<Self::A> {
+        Err(anyhow!("Part A is not solved yet"))
+    }
+
+    fn part_b(_input: &Self::Input) -> Result<Option<Self::B>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    test_real_input!({day});
+
+    const EXAMPLE: &str = "";
+
+    test_examples! {
+        Day{day},
+        test_example: EXAMPLE => (_, _),
+    }
+}
+"#;
+
+/// Return the source of the module for a new day
+fn render(day: usize, title: &str) -> String {
+    TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{title}", &format!("{:?}", title))
+}
+
+/// Add the day to the `solutions!` registry in the source of `main.rs`, keeping it ordered by day
+fn register(main: &str, day: usize) -> Result<String> {
+    let lines: Vec<&str> = main.lines().collect();
+    let start = lines
+        .iter()
+        .position(|line| line.trim() == "solutions! {")
+        .ok_or_else(|| anyhow!("Unable to find the solutions! registry"))?;
+    let end = lines[start..]
+        .iter()
+        .position(|line| line.trim() == "}")
+        .map(|i| start + i)
+        .ok_or_else(|| anyhow!("The solutions! registry is not terminated"))?;
+
+    let mut insert_at = end;
+    for (i, line) in lines.iter().enumerate().take(end).skip(start + 1) {
+        let registered: usize = line
+            .trim()
+            .strip_prefix("day")
+            .and_then(|rest| rest.split_once("::"))
+            .and_then(|(num, _)| num.parse().ok())
+            .ok_or_else(|| anyhow!("Unexpected line {:?} in the solutions! registry", line))?;
+        if registered == day {
+            return Err(anyhow!("Day {} is already registered", day));
+        }
+        if registered > day && insert_at == end {
+            insert_at = i;
+        }
+    }
+
+    let entry = format!("    day{day}::Day{day},");
+    let mut lines = lines;
+    lines.insert(insert_at, &entry);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Create the module and an empty input file for a new day and register it in `main.rs`. Nothing
+/// is written if the module already exists, and an existing input file is kept as is. Returns the
+/// files that were created or changed
+pub fn new_day(day: usize, title: &str, root: &Path) -> Result<Vec<PathBuf>> {
+    let module = root.join(format!("src/day{}.rs", day));
+    if module.exists() {
+        return Err(anyhow!("{:?} already exists", module));
+    }
+    let main = root.join("src/main.rs");
+    let registered = fs::read_to_string(&main)
+        .with_context(|| format!("Failed to read {:?}", main))
+        .and_then(|source| register(&source, day))?;
+
+    fs::write(&module, render(day, title))
+        .with_context(|| format!("Failed to write {:?}", module))?;
+    fs::write(&main, registered).with_context(|| format!("Failed to write {:?}", main))?;
+    let mut changed = vec![module, main];
+
+    let input = root.join(format!("data/day{}.txt", day));
+    if !input.exists() {
+        fs::write(&input, "").with_context(|| format!("Failed to write {:?}", input))?;
+        changed.push(input);
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAIN: &str = "mod report;\n\nsolutions! {\n    day1::Day1,\n    day3::Day3,\n}\n";
+
+    #[test]
+    fn test_render() {
+        let source = render(15, "Warehouse \"Woes\"");
+        assert!(source.contains("pub struct Day15;"));
+        assert!(source.contains("const TITLE: &'static str = \"Warehouse \\\"Woes\\\"\";"));
+        assert!(source.contains("test_real_input!(15);"));
+        assert!(source.contains("Result<Option<Self::B>> {\n        Ok(None)"));
+        assert!(!source.contains("{day}"));
+    }
+
+    #[test]
+    fn test_register() {
+        assert_eq!(
+            register(MAIN, 2).unwrap(),
+            "mod report;\n\nsolutions! {\n    day1::Day1,\n    day2::Day2,\n    day3::Day3,\n}\n"
+        );
+        assert!(register(MAIN, 4)
+            .unwrap()
+            .ends_with("day3::Day3,\n    day4::Day4,\n}\n"));
+        assert!(register(MAIN, 3).is_err());
+        assert!(register("fn main() {}\n", 3).is_err());
+    }
+}
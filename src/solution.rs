@@ -0,0 +1,233 @@
+use anyhow::{anyhow, Context as _, Result};
+
+use crate::answer::Answer;
+use crate::bench::{self, Phases};
+use crate::cancel;
+
+/// Return the day if it's one of the days of advent of code
+pub fn valid_day(day: usize) -> Result<usize> {
+    if !(1..=25).contains(&day) {
+        return Err(anyhow!("Day {} is not a valid day for advent of code", day));
+    }
+    Ok(day)
+}
+
+/// Interpret a day given on the command line
+pub fn parse_day(s: &str) -> Result<usize> {
+    valid_day(s.parse().with_context(|| format!("Invalid day {s:?}"))?)
+}
+
+/// A solution to the puzzle for a single day. The input is parsed once and then shared by both
+/// parts, which lets the runner time each phase individually
+pub trait Solution {
+    /// The day of the puzzle (1-25)
+    const DAY: usize;
+
+    /// The title of the puzzle
+    const TITLE: &'static str;
+
+    /// The parsed puzzle input
+    type Input;
+
+    /// The answer type for part A
+    type A: Into<Answer>;
+
+    /// The answer type for part B
+    type B: Into<Answer>;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part_a(input: &Self::Input) -> Result<Self::A>;
+
+    /// Solve part B, or return `None` while it isn't available, like before it's solved or on
+    /// the last day which has no part B
+    fn part_b(input: &Self::Input) -> Result<Option<Self::B>>;
+
+    /// Parse the input and solve both parts, stopping at the first error
+    fn solve(input: &str) -> Result<(Self::A, Option<Self::B>)>
+    where
+        Self: Sized,
+    {
+        let (answers, _) = solve_timed::<Self>(input)?;
+        Ok(answers)
+    }
+}
+
+/// The answers from a solution and the time spent in each phase
+#[derive(Debug)]
+pub struct Solved {
+    pub a: Answer,
+    pub b: Option<Answer>,
+    pub phases: Phases,
+}
+
+/// A type erased solution, which lets solutions with different input and answer types live in the
+/// same registry
+#[derive(Debug, Clone, Copy)]
+pub struct Entry {
+    pub day: usize,
+    pub title: &'static str,
+    run: fn(&str) -> Result<Solved>,
+    run_part: fn(&str, char) -> Result<Option<Answer>>,
+}
+
+impl Entry {
+    pub const fn of<S: Solution>() -> Self {
+        Self {
+            day: S::DAY,
+            title: S::TITLE,
+            run: solve_in_phases::<S>,
+            run_part: solve_part::<S>,
+        }
+    }
+
+    /// Solve the puzzle one phase at a time so that each phase can be timed individually
+    pub fn run(&self, input: &str) -> Result<Solved> {
+        (self.run)(input)
+    }
+
+    /// Solve only the given part (`a` or `b`), which works even if the other part fails
+    pub fn run_part(&self, input: &str, part: char) -> Result<Option<Answer>> {
+        (self.run_part)(input, part)
+    }
+}
+
+/// The answers to both parts of a solution
+type Answers<S> = (<S as Solution>::A, Option<<S as Solution>::B>);
+
+/// Solve the puzzle one phase at a time and time each phase. Stops at the first phase that fails,
+/// or between phases if the current thread is cancelled
+fn solve_timed<S: Solution>(input: &str) -> Result<(Answers<S>, Phases)> {
+    let token = cancel::current();
+    let (parsed, parse_time) = bench::time(|| S::parse(input));
+    let parsed = parsed?;
+    token.check()?;
+    let (a, part_a_time) = bench::time(|| S::part_a(&parsed));
+    let a = a?;
+    token.check()?;
+    let (b, part_b_time) = bench::time(|| S::part_b(&parsed));
+    let phases = Phases {
+        parse: parse_time,
+        part_a: part_a_time,
+        part_b: part_b_time,
+    };
+    Ok(((a, b?), phases))
+}
+
+fn solve_in_phases<S: Solution>(input: &str) -> Result<Solved> {
+    let ((a, b), phases) = solve_timed::<S>(input)?;
+    Ok(Solved {
+        a: a.into(),
+        b: b.map(Into::into),
+        phases,
+    })
+}
+
+fn solve_part<S: Solution>(input: &str, part: char) -> Result<Option<Answer>> {
+    let parsed = S::parse(input)?;
+    cancel::current().check()?;
+    match part {
+        'a' => Ok(Some(S::part_a(&parsed)?.into())),
+        _ => Ok(S::part_b(&parsed)?.map(Into::into)),
+    }
+}
+
+/// Declare the modules for each day and register their solutions. Adding a new day only requires
+/// adding it to the invocation of this macro
+macro_rules! solutions {
+    ($($module:ident::$solution:ident),* $(,)?) => {
+        $(mod $module;)*
+
+        /// All implemented solutions, ordered by day
+        pub static SOLUTIONS: &[$crate::solution::Entry] = &[
+            $($crate::solution::Entry::of::<$module::$solution>()),*
+        ];
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::SOLUTIONS;
+    use anyhow::anyhow;
+
+    struct FailingA;
+
+    impl Solution for FailingA {
+        const DAY: usize = 0;
+        const TITLE: &'static str = "Failing A";
+
+        type Input = ();
+        type A = usize;
+        type B = usize;
+
+        fn parse(_: &str) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part_a(_: &Self::Input) -> Result<Self::A> {
+            Err(anyhow!("Part A failed"))
+        }
+
+        fn part_b(_: &Self::Input) -> Result<Option<Self::B>> {
+            panic!("Part B must not run after part A failed");
+        }
+    }
+
+    struct OnlyA;
+
+    impl Solution for OnlyA {
+        const DAY: usize = 0;
+        const TITLE: &'static str = "Only A";
+
+        type Input = ();
+        type A = usize;
+        type B = usize;
+
+        fn parse(_: &str) -> Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part_a(_: &Self::Input) -> Result<Self::A> {
+            Ok(1)
+        }
+
+        fn part_b(_: &Self::Input) -> Result<Option<Self::B>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_without_part_b() {
+        let solved = Entry::of::<OnlyA>().run("").unwrap();
+        assert_eq!(solved.a, Answer::from(1usize));
+        assert_eq!(solved.b, None);
+        assert_eq!(OnlyA::solve("").unwrap(), (1, None));
+        assert_eq!(Entry::of::<OnlyA>().run_part("", 'b').unwrap(), None);
+    }
+
+    #[test]
+    fn test_stops_at_first_error() {
+        let err = Entry::of::<FailingA>().run("").unwrap_err();
+        assert_eq!(err.to_string(), "Part A failed");
+        assert!(FailingA::solve("").is_err());
+        assert!(Entry::of::<FailingA>().run_part("", 'a').is_err());
+    }
+
+    #[test]
+    fn test_parse_day() {
+        assert_eq!(parse_day("1").unwrap(), 1);
+        assert_eq!(parse_day("25").unwrap(), 25);
+        for s in ["0", "26", "-1", "a", ""] {
+            assert!(parse_day(s).is_err(), "{:?} was accepted", s);
+        }
+    }
+
+    #[test]
+    fn test_registry_is_ordered() {
+        for (prev, next) in SOLUTIONS.iter().zip(SOLUTIONS.iter().skip(1)) {
+            assert!(prev.day < next.day, "Day {} is out of order", next.day);
+        }
+        assert!(SOLUTIONS.iter().all(|s| valid_day(s.day).is_ok()));
+    }
+}
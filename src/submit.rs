@@ -0,0 +1,356 @@
+use anyhow::{anyhow, Context as _, Result};
+use std::fmt;
+use std::fs;
+use std::io::Write as _;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::answer::Answer;
+use crate::input::read_optional;
+
+/// The default location of the submission history
+pub const DEFAULT_HISTORY_PATH: &str = "data/submissions.txt";
+
+/// Interpret a part given on the command line, as either a letter or a number
+pub fn parse_part(s: &str) -> Result<char> {
+    match s {
+        "a" | "A" | "1" => Ok('a'),
+        "b" | "B" | "2" => Ok('b'),
+        _ => Err(anyhow!("Invalid part {:?}, expected a or b", s)),
+    }
+}
+
+/// What the site said about a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Feedback {
+    Correct,
+    TooHigh,
+    TooLow,
+
+    /// Wrong without saying in which direction
+    Wrong,
+
+    /// An answer was submitted too recently, and the site asked to wait this long from the given
+    /// time before submitting again
+    Wait(Duration, SystemTime),
+
+    /// Anything else, like submitting for a part that is already solved
+    Other(String),
+}
+
+impl Feedback {
+    /// Interpret the page returned at the given time after submitting an answer
+    pub fn parse(page: &str, now: SystemTime) -> Self {
+        let message = main_message(page);
+        if message.contains("That's the right answer") {
+            Self::Correct
+        } else if message.contains("your answer is too high") {
+            Self::TooHigh
+        } else if message.contains("your answer is too low") {
+            Self::TooLow
+        } else if message.contains("That's not the right answer") {
+            Self::Wrong
+        } else if let Some(wait) = wait_time(&message) {
+            Self::Wait(wait, now)
+        } else {
+            Self::Other(message)
+        }
+    }
+
+    /// The field used for the feedback in the history file. Waits are written as
+    /// `wait:<seconds>:<unix time>` to remember how long to wait and since when
+    fn keyword(&self) -> String {
+        match self {
+            Self::Correct => "correct".to_string(),
+            Self::TooHigh => "high".to_string(),
+            Self::TooLow => "low".to_string(),
+            Self::Wrong => "wrong".to_string(),
+            Self::Wait(wait, since) => {
+                let since = since.duration_since(UNIX_EPOCH).unwrap_or_default();
+                format!("wait:{}:{}", wait.as_secs(), since.as_secs())
+            }
+            Self::Other(_) => "other".to_string(),
+        }
+    }
+
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "correct" => Some(Self::Correct),
+            "high" => Some(Self::TooHigh),
+            "low" => Some(Self::TooLow),
+            "wrong" => Some(Self::Wrong),
+            "other" => Some(Self::Other(String::new())),
+            _ => {
+                let (wait, since) = keyword.strip_prefix("wait:")?.split_once(':')?;
+                let since = UNIX_EPOCH + Duration::from_secs(since.parse().ok()?);
+                Some(Self::Wait(Duration::from_secs(wait.parse().ok()?), since))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Feedback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "That's the right answer"),
+            Self::TooHigh => write!(f, "The answer is too high"),
+            Self::TooLow => write!(f, "The answer is too low"),
+            Self::Wrong => write!(f, "The answer is wrong"),
+            Self::Wait(wait, _) => write!(f, "Wait {}s before submitting again", wait.as_secs()),
+            Self::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Return the text of the `<article>` element of the page, which holds the message, with all tags
+/// removed. Pages without one are returned as they are
+fn main_message(page: &str) -> String {
+    let article = page
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once('>'))
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map_or(page, |(article, _)| article);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Find the wait time in a message like `You have 1m 5s left to wait`
+fn wait_time(message: &str) -> Option<Duration> {
+    let (before, _) = message.split_once(" left to wait")?;
+    let (_, amount) = before.rsplit_once("You have ")?;
+    let mut seconds = 0;
+    for part in amount.split_whitespace() {
+        let (n, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+        let n: u64 = n.parse().ok()?;
+        seconds += match unit {
+            "h" => n * 3600,
+            "m" => n * 60,
+            "s" => n,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+/// A previously submitted answer and what the site said about it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub day: usize,
+    pub part: char,
+    pub feedback: Feedback,
+    pub answer: String,
+}
+
+/// Every answer submitted so far. The file format is one `<day> <a|b> <feedback> <answer>` entry
+/// per line, where feedback is one of `correct`, `high`, `low`, `wrong`, `wait:<seconds>:<unix time>`
+/// or `other`
+#[derive(Debug, Default, PartialEq)]
+pub struct History(Vec<Attempt>);
+
+impl History {
+    /// Load the history from the given file. A missing file is the same as an empty one
+    pub fn load(path: &Path) -> Result<Self> {
+        let Some(contents) =
+            read_optional(path).with_context(|| format!("Failed to open history file {path:?}"))?
+        else {
+            return Ok(Self::default());
+        };
+        Self::parse(&contents).with_context(|| format!("Failed to parse history file {path:?}"))
+    }
+
+    fn parse(contents: &str) -> Result<Self> {
+        let mut history = Self::default();
+        for (i, line) in contents.lines().enumerate() {
+            let mut fields = line.splitn(4, ' ');
+            let (Some(day), Some(part), Some(feedback), Some(answer)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(anyhow!(
+                    "Expected <day> <part> <feedback> <answer> on line {}",
+                    i + 1
+                ));
+            };
+            history.0.push(Attempt {
+                day: day
+                    .parse()
+                    .with_context(|| format!("Invalid day on line {}", i + 1))?,
+                part: parse_part(part).with_context(|| format!("Line {}", i + 1))?,
+                feedback: Feedback::from_keyword(feedback)
+                    .ok_or_else(|| anyhow!("Invalid feedback {:?} on line {}", feedback, i + 1))?,
+                answer: answer.to_string(),
+            });
+        }
+        Ok(history)
+    }
+
+    /// Add an attempt to the history file
+    pub fn append(path: &Path, attempt: &Attempt) -> Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open history file {path:?}"))?;
+        writeln!(
+            file,
+            "{} {} {} {}",
+            attempt.day,
+            attempt.part,
+            attempt.feedback.keyword(),
+            attempt.answer
+        )
+        .with_context(|| format!("Failed to write history file {path:?}"))
+    }
+
+    /// Return how much longer the site asked to wait before submitting again, if at all. The wait
+    /// applies to every day and part
+    pub fn wait_left(&self, now: SystemTime) -> Option<Duration> {
+        self.0
+            .iter()
+            .filter_map(|attempt| match attempt.feedback {
+                Feedback::Wait(wait, since) => (since + wait).duration_since(now).ok(),
+                _ => None,
+            })
+            .filter(|left| !left.is_zero())
+            .max()
+    }
+
+    /// Return the reason the answer shouldn't be submitted at the given time without asking the
+    /// site, if any. That is either because it's known to be wrong or because the site asked to
+    /// wait
+    pub fn rejection(
+        &self,
+        day: usize,
+        part: char,
+        answer: &Answer,
+        now: SystemTime,
+    ) -> Option<String> {
+        if let Some(left) = self.wait_left(now) {
+            return Some(format!(
+                "the site asked to wait {}s more before submitting again",
+                left.as_secs_f64().ceil()
+            ));
+        }
+
+        let attempts = self.0.iter().filter(|a| a.day == day && a.part == part);
+        for attempt in attempts {
+            let previous = Answer::parse(&attempt.answer);
+            let reason = match (&attempt.feedback, answer.cmp_numeric(&previous)) {
+                (Feedback::Correct, _) if previous == *answer => {
+                    format!("{} was already accepted", answer)
+                }
+                (Feedback::Correct, _) => format!("the part was already solved with {}", previous),
+                (Feedback::TooHigh | Feedback::TooLow | Feedback::Wrong, _)
+                    if previous == *answer =>
+                {
+                    format!("{} was already submitted and was wrong", answer)
+                }
+                (Feedback::TooHigh, Some(ordering)) if ordering.is_ge() => {
+                    format!("{} was too high and {} isn't lower", previous, answer)
+                }
+                (Feedback::TooLow, Some(ordering)) if ordering.is_le() => {
+                    format!("{} was too low and {} isn't higher", previous, answer)
+                }
+                _ => continue,
+            };
+            return Some(reason);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_feedback() {
+        let parse = |message: &str| {
+            let page = format!(
+                "<html><main>\n<article><p>{}</p></article>\n</main></html>",
+                message
+            );
+            Feedback::parse(&page, UNIX_EPOCH)
+        };
+        assert_eq!(
+            parse("That's the right answer!  You are <em>one gold star</em> closer."),
+            Feedback::Correct
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too high.  If you're stuck..."),
+            Feedback::TooHigh
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too low."),
+            Feedback::TooLow
+        );
+        assert_eq!(
+            parse("That's not the right answer.  If you're stuck..."),
+            Feedback::Wrong
+        );
+        assert_eq!(
+            parse("You gave an answer too recently.  You have 1m 5s left to wait."),
+            Feedback::Wait(Duration::from_secs(65), UNIX_EPOCH)
+        );
+        assert_eq!(
+            parse("You don't seem to be solving <a>the right level</a>."),
+            Feedback::Other("You don't seem to be solving the right level.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_history() {
+        let history =
+            History::parse("3 a high 100\n3 a low 10\n3 a wrong 50\n4 b correct 7\n").unwrap();
+        let rejected = |day, part, answer: usize| {
+            history
+                .rejection(day, part, &Answer::from(answer), UNIX_EPOCH)
+                .is_some()
+        };
+        assert!(rejected(3, 'a', 100));
+        assert!(rejected(3, 'a', 120));
+        assert!(rejected(3, 'a', 10));
+        assert!(rejected(3, 'a', 50));
+        assert!(!rejected(3, 'a', 42));
+        assert!(!rejected(3, 'b', 120));
+        assert!(rejected(4, 'b', 7));
+        assert!(rejected(4, 'b', 8));
+        assert!(History::parse("3 c high 100\n").is_err());
+
+        // Answers that aren't numbers are compared as they are written
+        let history = History::parse("5 a wrong 007\n5 b wrong 12\n").unwrap();
+        let rejected =
+            |part, answer: Answer| history.rejection(5, part, &answer, UNIX_EPOCH).is_some();
+        assert!(rejected('a', Answer::from("007")));
+        assert!(!rejected('a', Answer::from(7usize)));
+        assert!(rejected('b', Answer::from("12")));
+    }
+
+    #[test]
+    fn test_wait() {
+        let given = UNIX_EPOCH + Duration::from_secs(1000);
+        let wait = Feedback::Wait(Duration::from_secs(60), given);
+        assert_eq!(wait.keyword(), "wait:60:1000");
+        assert_eq!(Feedback::from_keyword("wait:60:1000"), Some(wait));
+        assert_eq!(Feedback::from_keyword("wait:60"), None);
+        assert_eq!(Feedback::from_keyword("wait"), None);
+
+        // Waiting applies to every day and part until the time is up
+        let history = History::parse("3 a wait:60:1000 42\n").unwrap();
+        let answer = Answer::from(7usize);
+        let at = |secs| UNIX_EPOCH + Duration::from_secs(secs);
+        assert_eq!(history.wait_left(at(1020)), Some(Duration::from_secs(40)));
+        assert!(history.rejection(4, 'b', &answer, at(1020)).is_some());
+        assert_eq!(history.wait_left(at(1060)), None);
+        assert!(history.rejection(4, 'b', &answer, at(1060)).is_none());
+    }
+}
@@ -1,26 +1,344 @@
+// Shared helpers for the days
+pub mod checked;
+pub mod cycle;
+pub mod grid;
+pub mod parse;
+pub mod point;
+pub mod search;
+pub mod union_find;
+
 #[macro_export]
 macro_rules! test_real_input {
+    ($(#[$attrs:meta])* $day:literal) => {
+        $(#[$attrs])*
+        #[test]
+        fn test_real_input() {
+            let Some(input) = $crate::utils::read_real_input($day) else {
+                return;
+            };
+            let (expected_answer_a, expected_answer_b) = $crate::utils::read_expected_answers($day);
+            test_real_input!(@check $day, input, expected_answer_a, expected_answer_b);
+        }
+    };
     ($(#[$attrs:meta])* $day:literal, $answer_a:expr) => {
-        test_real_input!($(#[$attrs])* $day, $answer_a, None);
+        test_real_input!(@test $(#[$attrs])* $day, $answer_a, None::<$crate::answer::Answer>);
     };
     ($(#[$attrs:meta])* $day:literal, $answer_a:expr, $answer_b:expr) => {
+        test_real_input!(@test $(#[$attrs])* $day, $answer_a, Some($answer_b));
+    };
+    (@test $(#[$attrs:meta])* $day:literal, $answer_a:expr, $answer_b:expr) => {
         $(#[$attrs])*
         #[test]
         fn test_real_input() {
-            let input = std::fs::read_to_string(&format!("data/day{}.txt", $day)).unwrap();
-            let (a, b) = match main(&input) {
-                Ok(answers) => answers,
-                Err(e) => panic!("Solution failed to complete: {}", e),
+            let Some(input) = $crate::utils::read_real_input($day) else {
+                return;
             };
 
-            let expected_answer_a = $answer_a;
-            let expected_answer_b = Option::from($answer_b);
+            test_real_input!(
+                @check
+                $day,
+                input,
+                $crate::utils::expected($answer_a),
+                $crate::utils::expected_opt($answer_b)
+            );
+        }
+    };
+    (@check $day:literal, $input:expr, $expected_answer_a:expr, $expected_answer_b:expr) => {
+        let input: String = $input;
+        let solution = $crate::SOLUTIONS
+            .iter()
+            .find(|solution| solution.day == $day)
+            .expect("Solution is not registered");
+        let solved = match solution.run(&input) {
+            Ok(solved) => solved,
+            Err(e) => panic!("Solution failed to complete: {}", e),
+        };
+
+        let expected_answer_a: $crate::answer::Answer = $expected_answer_a;
+        let expected_answer_b: Option<$crate::answer::Answer> = $expected_answer_b;
+        if expected_answer_b.is_none() {
+            assert_eq!(solved.a, expected_answer_a);
+        } else {
+            assert_eq!((solved.a, solved.b), (expected_answer_a, expected_answer_b));
+        }
+    };
+}
 
-            if expected_answer_b.is_none() {
-                assert_eq!(a, expected_answer_a);
-            } else {
-                assert_eq!((a, b), (expected_answer_a, expected_answer_b));
+/// Generate one test per named example. Each example gives the input and the expected answers for
+/// part A and part B, where `_` skips that part. Answers may be any expression, like `-5`
+///
+/// ```ignore
+/// test_examples! {
+///     Day3,
+///     test_example_a: EXAMPLE_A => (161, _),
+///     test_example_b: EXAMPLE_B => (_, 48),
+/// }
+/// ```
+#[macro_export]
+macro_rules! test_examples {
+    ($solution:ty, $($name:ident: $input:expr => ($($answers:tt)*)),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                let (expected_a, expected_b) = test_examples!(@expected $($answers)*);
+                $crate::utils::check_example::<$solution>(
+                    stringify!($name),
+                    $input,
+                    expected_a,
+                    expected_b,
+                );
             }
-        }
+        )*
+    };
+    (@expected _, _) => {
+        (None, None)
+    };
+    (@expected _, $b:expr) => {
+        (None, Some($b))
+    };
+    (@expected $a:expr, _) => {
+        (Some($a), None)
+    };
+    (@expected $a:expr, $b:expr) => {
+        (Some($a), Some($b))
     };
 }
+
+/// Set this environment variable to make real input tests fail instead of being skipped when the
+/// input file is missing
+#[cfg(test)]
+pub const REQUIRE_INPUT_VAR: &str = "AOC_REQUIRE_INPUT";
+
+/// Read the real puzzle input for the given day. Puzzle inputs are personal and may not be
+/// available, so a missing file skips the test with a message instead of failing it, unless
+/// `AOC_REQUIRE_INPUT` is set
+#[cfg(test)]
+pub fn read_real_input(day: usize) -> Option<String> {
+    let path = format!("data/day{}.txt", day);
+    let require = std::env::var_os(REQUIRE_INPUT_VAR).is_some();
+    read_input_or_skip(std::path::Path::new(&path), require)
+}
+
+/// Read the expected answers for the real input of the given day. Panics if there is no answer
+/// for part A, since an input without answers can't be checked
+#[cfg(test)]
+pub fn read_expected_answers(day: usize) -> (crate::answer::Answer, Option<crate::answer::Answer>) {
+    use crate::answer::Answer;
+
+    let answers = crate::answers::Answers::load(crate::answers::DEFAULT_PATH).unwrap();
+    let Some(expected_a) = answers.a(day).map(Answer::parse) else {
+        panic!("No expected answer for day {}", day);
+    };
+    (expected_a, answers.b(day).map(Answer::parse))
+}
+
+/// An expected answer given to `test_real_input!`. Integers are only implemented for `i128`, which
+/// makes unsuffixed literals of any size infer to it instead of falling back to `i32`
+#[cfg(test)]
+pub trait Expected {
+    fn into_answer(self) -> crate::answer::Answer;
+}
+
+#[cfg(test)]
+impl Expected for i128 {
+    fn into_answer(self) -> crate::answer::Answer {
+        use crate::answer::Answer;
+
+        match u64::try_from(self) {
+            Ok(n) => Answer::Integer(n),
+            Err(_) => Answer::Signed(i64::try_from(self).expect("Expected answer is too large")),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Expected for &str {
+    fn into_answer(self) -> crate::answer::Answer {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+impl Expected for String {
+    fn into_answer(self) -> crate::answer::Answer {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+impl Expected for crate::answer::Answer {
+    fn into_answer(self) -> crate::answer::Answer {
+        self
+    }
+}
+
+#[cfg(test)]
+pub fn expected(answer: impl Expected) -> crate::answer::Answer {
+    answer.into_answer()
+}
+
+#[cfg(test)]
+pub fn expected_opt<T: Expected>(answer: Option<T>) -> Option<crate::answer::Answer> {
+    answer.map(Expected::into_answer)
+}
+
+/// Tell the user that a real input test was skipped
+#[cfg(test)]
+fn skip_real_input(reason: &str) {
+    use std::io::Write as _;
+
+    // Write to stderr directly since the test harness captures the output of eprintln!
+    let _ = writeln!(
+        std::io::stderr(),
+        "Skipping real input test: {} (set {} to fail instead)",
+        reason,
+        REQUIRE_INPUT_VAR,
+    );
+}
+
+/// Solve an example and compare against the expected answers, reporting every part that differs
+/// along with the input when the check fails
+#[cfg(test)]
+pub fn check_example<S: crate::solution::Solution>(
+    name: &str,
+    input: &str,
+    expected_a: Option<S::A>,
+    expected_b: Option<S::B>,
+) where
+    S::A: PartialEq + std::fmt::Debug,
+    S::B: PartialEq + std::fmt::Debug,
+{
+    use std::fmt::Write as _;
+
+    let parsed = S::parse(input)
+        .unwrap_or_else(|e| panic!("Failed to parse {} for day {}: {:#}", name, S::DAY, e));
+
+    let mut mismatches = String::new();
+    if let Some(expected) = expected_a {
+        let actual = S::part_a(&parsed);
+        if actual.as_ref().ok() != Some(&expected) {
+            let _ = writeln!(
+                mismatches,
+                "  Part A: expected {:?}, got {:?}",
+                expected, actual
+            );
+        }
+    }
+    if let Some(expected) = expected_b {
+        let actual = S::part_b(&parsed);
+        if actual.as_ref().ok().and_then(Option::as_ref) != Some(&expected) {
+            let _ = writeln!(
+                mismatches,
+                "  Part B: expected {:?}, got {:?}",
+                expected, actual
+            );
+        }
+    }
+
+    if !mismatches.is_empty() {
+        panic!(
+            "Example {} for day {} failed\n{}Input:\n{}",
+            name,
+            S::DAY,
+            mismatches,
+            input.trim_end(),
+        );
+    }
+}
+
+#[cfg(test)]
+fn read_input_or_skip(path: &std::path::Path, require: bool) -> Option<String> {
+    use std::io::ErrorKind;
+
+    // New days start out with an empty placeholder file until the input is downloaded
+    match std::fs::read_to_string(path) {
+        Ok(input) if input.trim().is_empty() => {
+            if require {
+                panic!("Input file {} is empty", path.display());
+            }
+            skip_real_input(&format!("{} is empty", path.display()));
+            None
+        }
+        Ok(input) => Some(input),
+        Err(e) if e.kind() == ErrorKind::NotFound && !require => {
+            skip_real_input(&format!("{} not found", path.display()));
+            None
+        }
+        Err(e) => panic!("Failed to read {}: {}", path.display(), e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solution::Solution;
+    use anyhow::Result;
+
+    struct Doubling;
+
+    impl Solution for Doubling {
+        const DAY: usize = 0;
+        const TITLE: &'static str = "Doubling";
+
+        type Input = isize;
+        type A = isize;
+        type B = isize;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            Ok(input.trim().parse()?)
+        }
+
+        fn part_a(input: &Self::Input) -> Result<Self::A> {
+            Ok(input * 2)
+        }
+
+        fn part_b(input: &Self::Input) -> Result<Option<Self::B>> {
+            Ok(Some(input * 4))
+        }
+    }
+
+    test_examples! {
+        Doubling,
+        test_example_both: "2" => (4, 8),
+        test_example_skip_a: "3" => (_, 12),
+        test_example_skip_b: "3" => (6, _),
+        test_example_negative: "-2" => (-4, -8),
+    }
+
+    #[test]
+    fn test_expected() {
+        use crate::answer::Answer;
+
+        assert_eq!(expected(-3), Answer::Signed(-3));
+        assert_eq!(expected("6,5"), Answer::from("6,5"));
+        assert_eq!(expected_opt(None::<Answer>), None);
+
+        // Unsuffixed literals of any size work, like the answers for day 7
+        assert_eq!(
+            expected(1_289_579_105_366),
+            Answer::Integer(1_289_579_105_366)
+        );
+        assert_eq!(
+            expected_opt(Some(92_148_721_834_692)),
+            Some(Answer::Integer(92_148_721_834_692))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Part B: expected 9, got Ok(Some(8))")]
+    fn test_example_mismatch() {
+        check_example::<Doubling>("mismatch", "2", Some(4), Some(9));
+    }
+
+    #[test]
+    fn test_missing_input_is_skipped() {
+        let path = std::path::Path::new("data/does-not-exist.txt");
+        assert_eq!(read_input_or_skip(path, false), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to read data/does-not-exist.txt")]
+    fn test_missing_input_is_required() {
+        read_input_or_skip(std::path::Path::new("data/does-not-exist.txt"), true);
+    }
+}
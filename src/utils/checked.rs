@@ -0,0 +1,65 @@
+use anyhow::Result;
+
+/// Define arithmetic functions that report overflow as an error when the `checked` feature is
+/// enabled. Without the feature they compile down to the plain operators
+macro_rules! checked_ops {
+    ($($(#[$attrs:meta])* $name:ident($rhs:ty) => $checked:ident, $op:literal;)*) => {
+        $(
+            $(#[$attrs])*
+            #[cfg(feature = "checked")]
+            pub fn $name(a: usize, b: $rhs) -> Result<usize> {
+                a.$checked(b)
+                    .ok_or_else(|| anyhow::anyhow!("Arithmetic overflow in {} {} {}", a, $op, b))
+            }
+
+            $(#[$attrs])*
+            #[cfg(not(feature = "checked"))]
+            #[inline(always)]
+            pub fn $name(a: usize, b: $rhs) -> Result<usize> {
+                Ok(checked_ops!(@op $name, a, b))
+            }
+        )*
+    };
+    (@op add, $a:ident, $b:ident) => { $a + $b };
+    (@op sub, $a:ident, $b:ident) => { $a - $b };
+    (@op mul, $a:ident, $b:ident) => { $a * $b };
+    (@op pow, $a:ident, $b:ident) => { $a.pow($b) };
+}
+
+checked_ops! {
+    /// Return `a + b`
+    add(usize) => checked_add, "+";
+    /// Return `a - b`
+    #[allow(dead_code)]
+    sub(usize) => checked_sub, "-";
+    /// Return `a * b`
+    mul(usize) => checked_mul, "*";
+    /// Return `a` raised to the power of `b`
+    pow(u32) => checked_pow, "^";
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_in_range() {
+        assert_eq!(add(2, 3).unwrap(), 5);
+        assert_eq!(sub(5, 3).unwrap(), 2);
+        assert_eq!(mul(4, 3).unwrap(), 12);
+        assert_eq!(pow(10, 3).unwrap(), 1000);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        let err = mul(usize::MAX, 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Arithmetic overflow in {} * 2", usize::MAX)
+        );
+        assert!(add(usize::MAX, 1).is_err());
+        assert!(sub(0, 1).is_err());
+        assert!(pow(10, 40).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A repeating part of a sequence of states. The state at index `start` is the first one that is
+/// repeated and the sequence repeats every `length` steps from there on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Return the smallest number of steps that ends up in the same state as taking `n` steps
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Find where the cycle starts once its length is known, by walking two states that are one cycle
+/// apart until they meet
+fn cycle_start<S: Clone + PartialEq>(
+    initial: S,
+    length: usize,
+    step: &mut impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let mut behind = initial.clone();
+    let mut ahead = initial;
+    for _ in 0..length {
+        ahead = step(&ahead)?;
+    }
+
+    let mut start = 0;
+    while behind != ahead {
+        behind = step(&behind)?;
+        ahead = step(&ahead)?;
+        start += 1;
+    }
+    Some(Cycle { start, length })
+}
+
+/// Find the cycle using Floyd's tortoise and hare, which only keeps a few states in memory. The
+/// step function returns `None` when the sequence ends, in which case there is no cycle
+#[allow(dead_code)]
+pub fn floyd<S: Clone + PartialEq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let mut tortoise = step(&initial)?;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        hare = step(&hare)?;
+    }
+
+    // The hare has caught up from behind, so walk once around the cycle to measure it
+    let mut length = 1;
+    hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    cycle_start(initial, length, &mut step)
+}
+
+/// Find the cycle using Brent's algorithm, which takes fewer steps than Floyd's while also only
+/// keeping a few states in memory. The step function returns `None` when the sequence ends, in
+/// which case there is no cycle
+pub fn brent<S: Clone + PartialEq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial)?;
+    while tortoise != hare {
+        // Teleport the tortoise to the hare every power of two steps
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    cycle_start(initial, length, &mut step)
+}
+
+/// Find the cycle by remembering every state seen so far. This takes the fewest steps and only
+/// needs a key that identifies each state, at the cost of memory for every visited state. The step
+/// function returns `None` when the sequence ends, in which case there is no cycle
+pub fn find_cycle<S, K: Eq + Hash>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+    mut key: impl FnMut(&S) -> K,
+) -> Option<Cycle> {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    let mut i = 0;
+    loop {
+        if let Some(start) = seen.insert(key(&state), i) {
+            return Some(Cycle {
+                start,
+                length: i - start,
+            });
+        }
+        state = step(&state)?;
+        i += 1;
+    }
+}
+
+/// Return the state after `n` steps. Every state is remembered by its key, so once a state repeats
+/// the remaining full cycles are skipped. Sequences that don't repeat within `n` steps are simply
+/// simulated to the end
+#[allow(dead_code)]
+pub fn state_after<S, K: Eq + Hash>(
+    initial: S,
+    n: usize,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> S {
+    let mut seen = HashMap::new();
+    let mut state = initial;
+    let mut i = 0;
+    while i < n {
+        if let Some(start) = seen.insert(key(&state), i) {
+            let cycle = Cycle {
+                start,
+                length: i - start,
+            };
+            for _ in 0..(cycle.equivalent(n) - cycle.equivalent(i)) {
+                state = step(&state);
+            }
+            return state;
+        }
+        state = step(&state);
+        i += 1;
+    }
+    state
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A sequence that starts at 0, enters the cycle 3 -> 4 -> ... -> 9 -> 3 and never ends
+    fn rho(&n: &usize) -> Option<usize> {
+        Some(if n == 9 { 3 } else { n + 1 })
+    }
+
+    const RHO: Cycle = Cycle {
+        start: 3,
+        length: 7,
+    };
+
+    #[test]
+    fn test_find_cycles() {
+        assert_eq!(floyd(0, rho), Some(RHO));
+        assert_eq!(brent(0, rho), Some(RHO));
+        assert_eq!(find_cycle(0, rho, |&n| n), Some(RHO));
+
+        // Starting inside the cycle
+        let cycle = Cycle {
+            start: 0,
+            length: 7,
+        };
+        assert_eq!(brent(5, rho), Some(cycle));
+        assert_eq!(floyd(5, rho), Some(cycle));
+    }
+
+    #[test]
+    fn test_no_cycle() {
+        let step = |&n: &usize| (n < 10).then_some(n + 1);
+        assert_eq!(floyd(0, step), None);
+        assert_eq!(brent(0, step), None);
+        assert_eq!(find_cycle(0, step, |&n| n), None);
+    }
+
+    #[test]
+    fn test_state_after() {
+        assert_eq!(RHO.equivalent(2), 2);
+        assert_eq!(RHO.equivalent(10), 3);
+        let rho_step = |n: &usize| rho(n).unwrap();
+        assert_eq!(
+            state_after(0, 1_000_000_000_000, rho_step, |&n| n),
+            3 + (1_000_000_000_000 - 3) % 7
+        );
+        assert_eq!(state_after(0, 2, rho_step, |&n| n), 2);
+
+        // A sequence that never repeats is only stepped n times
+        assert_eq!(state_after(0u64, 3, |n| n + 1, |&n| n), 3);
+    }
+}
@@ -0,0 +1,262 @@
+use anyhow::{anyhow, Context as _, Result};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use super::parse::{column_context, line_context};
+use super::point::Point;
+
+/// A dense two dimensional grid stored row by row. The origin is the top left corner and positions
+/// outside the grid are caught by the bounds checks
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// Create a grid where every cell has the same value
+    #[allow(dead_code)]
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![value; width * height],
+            width,
+            height,
+        }
+    }
+
+    /// Parse a grid from text where every line is a row and every character is a cell. All rows
+    /// must have the same length
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        let mut cells = Vec::with_capacity(input.len());
+        let mut width = None;
+        let mut height = 0;
+        for (y, line) in input.lines().enumerate() {
+            let mut line_width = 0;
+            for (x, c) in line.chars().enumerate() {
+                let value = cell(c)
+                    .with_context(|| column_context(x + 1))
+                    .with_context(|| line_context(y + 1, line))?;
+                cells.push(value);
+                line_width += 1;
+            }
+
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(anyhow!("Expected {} cells, found {}", width, line_width))
+                        .with_context(|| line_context(y + 1, line));
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[allow(dead_code)]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Return true if the position is inside the grid
+    pub fn contains(&self, p: Point) -> bool {
+        (0..self.width as isize).contains(&p.x) && (0..self.height as isize).contains(&p.y)
+    }
+
+    fn offset(&self, p: Point) -> Option<usize> {
+        self.contains(p)
+            .then(|| p.y as usize * self.width + p.x as usize)
+    }
+
+    fn position(&self, offset: usize) -> Point {
+        Point::new(
+            (offset % self.width) as isize,
+            (offset / self.width) as isize,
+        )
+    }
+
+    /// Return the cell at the given position, or `None` if it is outside the grid
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.offset(p).map(|i| &self.cells[i])
+    }
+
+    /// Return the cell at the given position mutably, or `None` if it is outside the grid
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.offset(p).map(|i| &mut self.cells[i])
+    }
+
+    /// Iterate over every position and its cell, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.position(i), cell))
+    }
+
+    /// Create a new grid of the same size by applying a function to every cell
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Iterate over the orthogonal neighbors of a position that are inside the grid
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbors4().into_iter().filter(|&n| self.contains(n))
+    }
+
+    /// Iterate over the orthogonal and diagonal neighbors of a position that are inside the grid
+    #[allow(dead_code)]
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbors8().into_iter().filter(|&n| self.contains(n))
+    }
+
+    /// Iterate over the rows from top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // Guard against zero width since chunks panics on a chunk size of zero
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Iterate over the columns from left to right
+    #[allow(dead_code)]
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.cells.iter().skip(x).step_by(self.width))
+    }
+
+    /// Return the position of the first cell with the given value
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.cells
+            .iter()
+            .position(|cell| cell == value)
+            .map(|i| self.position(i))
+    }
+
+    /// Iterate over the positions of all cells with the given value
+    pub fn positions<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(p, _)| p)
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{} is outside the grid", p))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{} is outside the grid", p))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows().take(self.height) {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "abc\ndef\n";
+
+    fn example() -> Grid<char> {
+        Grid::parse(EXAMPLE, Ok).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Point::new(1, 1)), Some(&'e'));
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.to_string(), EXAMPLE);
+
+        assert!(Grid::parse("ab\nc", Ok).is_err());
+        let err = Grid::parse("1x", |c| {
+            c.to_digit(10).ok_or_else(|| anyhow!("Not a digit"))
+        });
+        assert_eq!(
+            format!("{:#}", err.unwrap_err()),
+            "Line 1 \"1x\": Column 2: Not a digit"
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = example();
+        assert_eq!(
+            grid.neighbors4(Point::new(0, 0)).collect::<Vec<_>>(),
+            [Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(
+            grid.neighbors8(Point::new(1, 0)).collect::<Vec<_>>(),
+            [
+                Point::new(2, 0),
+                Point::new(2, 1),
+                Point::new(1, 1),
+                Point::new(0, 1),
+                Point::new(0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = example();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            [['a', 'b', 'c'], ['d', 'e', 'f']]
+        );
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, ["ad", "be", "cf"]);
+    }
+
+    #[test]
+    fn test_find() {
+        let mut grid = example();
+        assert_eq!(grid.find(&'e'), Some(Point::new(1, 1)));
+        assert_eq!(grid.find(&'x'), None);
+
+        grid[Point::new(2, 1)] = 'e';
+        assert_eq!(
+            grid.positions(&'e').collect::<Vec<_>>(),
+            [Point::new(1, 1), Point::new(2, 1)]
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use anyhow::{anyhow, Context as _, Result};
+use regex::Regex;
+use std::error::Error as StdError;
+use std::str::FromStr;
+
+use super::grid::Grid;
+
+/// Return the 1-based column where `part` starts within `line`, ignoring leading whitespace since
+/// values are trimmed before they are parsed. Assumes `part` is a slice of `line`
+fn column(line: &str, part: &str) -> usize {
+    let part = part.trim_start();
+    (part.as_ptr() as usize).saturating_sub(line.as_ptr() as usize) + 1
+}
+
+/// Describe a line of the input in an error, like `Line 2 "3 x4"`. Line numbers are 1-based
+pub fn line_context(number: usize, line: &str) -> String {
+    format!("Line {} {:?}", number, line)
+}
+
+/// Describe a column of a line in an error, like `Column 3`. Column numbers are 1-based
+pub fn column_context(column: usize) -> String {
+    format!("Column {}", column)
+}
+
+/// A block of lines that remembers where it starts in the original input, so line numbers in
+/// errors refer to the whole input rather than the block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    pub first_line: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+    /// Parse every line of the section. Errors are tagged with the line number and its content
+    pub fn lines<T>(&self, mut parse: impl FnMut(&'a str) -> Result<T>) -> Result<Vec<T>> {
+        self.text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| parse(line).with_context(|| line_context(self.first_line + i, line)))
+            .collect()
+    }
+}
+
+/// Parse every line of the input. Errors are tagged with the line number and its content
+pub fn lines<'a, T>(input: &'a str, parse: impl FnMut(&'a str) -> Result<T>) -> Result<Vec<T>> {
+    Section {
+        first_line: 1,
+        text: input,
+    }
+    .lines(parse)
+}
+
+/// Split the input into blocks separated by blank lines
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        let is_blank = line.trim().is_empty();
+        match (start, is_blank) {
+            (None, false) => start = Some((i + 1, offset)),
+            (Some((first_line, from)), true) => {
+                sections.push(Section {
+                    first_line,
+                    text: &input[from..offset],
+                });
+                start = None;
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    if let Some((first_line, from)) = start {
+        sections.push(Section {
+            first_line,
+            text: &input[from..],
+        });
+    }
+    sections
+}
+
+/// Parse a single value, ignoring surrounding whitespace
+pub fn value<T>(s: &str) -> Result<T>
+where
+    T: FromStr,
+    T::Err: StdError + Send + Sync + 'static,
+{
+    s.trim()
+        .parse()
+        .with_context(|| format!("Invalid value {:?}", s.trim()))
+}
+
+/// Parse every value in a slice of a line, tagging errors with the column of the value
+fn values_in<'a, T>(line: &'a str, parts: impl Iterator<Item = &'a str>) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: StdError + Send + Sync + 'static,
+{
+    parts
+        .map(|part| value(part).with_context(|| column_context(column(line, part))))
+        .collect()
+}
+
+/// Parse a list of whitespace separated values
+pub fn integers<T>(s: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: StdError + Send + Sync + 'static,
+{
+    integers_in(s, s)
+}
+
+/// Parse a list of whitespace separated values in `s`, which is a slice of `line`. Columns in
+/// errors refer to the whole line
+pub fn integers_in<T>(line: &str, s: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: StdError + Send + Sync + 'static,
+{
+    values_in(line, s.split_whitespace())
+}
+
+/// Parse a list of values with the given separator between them
+pub fn separated<T>(s: &str, separator: &str) -> Result<Vec<T>>
+where
+    T: FromStr,
+    T::Err: StdError + Send + Sync + 'static,
+{
+    values_in(s, s.split(separator))
+}
+
+/// Parse two values on either side of the first occurrence of the separator
+pub fn pair<A, B>(s: &str, separator: &str) -> Result<(A, B)>
+where
+    A: FromStr,
+    A::Err: StdError + Send + Sync + 'static,
+    B: FromStr,
+    B::Err: StdError + Send + Sync + 'static,
+{
+    let (a, b) = s
+        .split_once(separator)
+        .ok_or_else(|| anyhow!("Expected {:?} between two values", separator))?;
+    Ok((
+        value(a).with_context(|| column_context(column(s, a)))?,
+        value(b).with_context(|| column_context(column(s, b)))?,
+    ))
+}
+
+/// Match a regex against the string and return the text of its `N` capture groups. The regex must
+/// be anchored like `^...$` to consider every way of matching the whole string. Without anchors
+/// only the leftmost match is tried, and it's an error unless it happens to cover the whole string.
+///
+/// Panics if the regex doesn't have exactly `N` capture groups, or if any of them is optional
+pub fn captures<'a, const N: usize>(re: &Regex, s: &'a str) -> Result<[&'a str; N]> {
+    re.captures(s)
+        .filter(|caps| caps.get(0).is_some_and(|m| m.range() == (0..s.len())))
+        .map(|caps| caps.extract().1)
+        .ok_or_else(|| anyhow!("Expected text matching {}", re.as_str()))
+}
+
+/// Parse a string of single digits
+pub fn digits(s: &str) -> Result<Vec<u32>> {
+    s.char_indices()
+        .map(|(i, c)| {
+            c.to_digit(10)
+                .ok_or_else(|| anyhow!("Invalid digit {:?}", c))
+                .with_context(|| column_context(i + 1))
+        })
+        .collect()
+}
+
+/// Parse a grid of single digits
+pub fn digit_grid(input: &str) -> Result<Grid<u32>> {
+    Grid::parse(input, |c| {
+        c.to_digit(10)
+            .ok_or_else(|| anyhow!("Invalid digit {:?}", c))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn error<T: std::fmt::Debug>(result: Result<T>) -> String {
+        format!("{:#}", result.unwrap_err())
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(lines("1 2\n3 4", integers::<u8>).unwrap(), [[1, 2], [3, 4]]);
+        assert_eq!(
+            error(lines("1 2\n3 x4", integers::<u8>)),
+            "Line 2 \"3 x4\": Column 3: Invalid value \"x4\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_integers_in() {
+        let line = "3267: 81 4x 27";
+        let (_, nums) = line.split_once(": ").unwrap();
+        assert_eq!(
+            error(integers_in::<u8>(line, nums)),
+            "Column 10: Invalid value \"4x\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "a\nb\n\n\nc\n";
+        let found = sections(input);
+        assert_eq!(
+            found,
+            [
+                Section {
+                    first_line: 1,
+                    text: "a\nb\n"
+                },
+                Section {
+                    first_line: 5,
+                    text: "c\n"
+                },
+            ]
+        );
+        assert_eq!(
+            error(found[1].lines(value::<u8>)),
+            "Line 5 \"c\": Invalid value \"c\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_pair_and_separated() {
+        assert_eq!(pair::<u8, u8>("3   4", " ").unwrap(), (3, 4));
+        assert_eq!(pair::<u8, i8>("5|-6", "|").unwrap(), (5, -6));
+        assert_eq!(
+            error(pair::<u8, u8>("3   x", " ")),
+            "Column 5: Invalid value \"x\": invalid digit found in string"
+        );
+        assert_eq!(
+            error(pair::<u8, u8>("5,6", "|")),
+            "Expected \"|\" between two values"
+        );
+        assert_eq!(separated::<u8>("1,2,3", ",").unwrap(), [1, 2, 3]);
+        assert!(error(separated::<u8>("1,,3", ",")).starts_with("Column 3: Invalid value \"\""));
+        assert!(error(separated::<u8>("1, x", ",")).starts_with("Column 4: Invalid value \"x\""));
+    }
+
+    #[test]
+    fn test_captures() {
+        let re = Regex::new(r"^X=(\d+), Y=(\d+)$").unwrap();
+        assert_eq!(captures(&re, "X=1, Y=22").unwrap(), ["1", "22"]);
+        assert!(error(captures::<2>(&re, "X=1")).starts_with("Expected text matching"));
+
+        let unanchored = Regex::new(r"X=(\d+), Y=(\d+)").unwrap();
+        assert_eq!(captures(&unanchored, "X=1, Y=22").unwrap(), ["1", "22"]);
+        assert!(captures::<2>(&unanchored, "X=1, Y=22, Z=3").is_err());
+        assert!(captures::<2>(&unanchored, " X=1, Y=22").is_err());
+
+        // Anchors make the regex try the longer alternative
+        assert!(captures::<0>(&Regex::new("a|ab").unwrap(), "ab").is_err());
+        assert!(captures::<0>(&Regex::new("^(?:a|ab)$").unwrap(), "ab").is_ok());
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digits("102").unwrap(), [1, 0, 2]);
+        assert_eq!(error(digits("1a")), "Column 2: Invalid digit 'a'");
+        assert_eq!(digit_grid("12\n34").unwrap().to_string(), "12\n34\n");
+        assert_eq!(
+            error(digit_grid("12\n3x")),
+            "Line 2 \"3x\": Column 2: Invalid digit 'x'"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use anyhow::{anyhow, Error, Result};
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or offset on a two dimensional grid where `y` grows downwards
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    #[allow(dead_code)]
+    pub const ORIGIN: Self = Self::new(0, 0);
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// Return the number of orthogonal steps between two points
+    #[allow(dead_code)]
+    pub fn manhattan(self, other: Self) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Wrap the point around so it ends up in `0..size.x` and `0..size.y`
+    pub fn rem_euclid(self, size: Self) -> Self {
+        Self::new(self.x.rem_euclid(size.x), self.y.rem_euclid(size.y))
+    }
+
+    /// Return the point one step away in the given direction
+    pub fn step(self, direction: Direction) -> Self {
+        self + direction.offset()
+    }
+
+    /// Return the orthogonal neighbors, clockwise starting from up
+    pub fn neighbors4(self) -> [Self; 4] {
+        Direction::ORTHOGONAL.map(|d| self.step(d))
+    }
+
+    /// Return the orthogonal and diagonal neighbors, clockwise starting from up
+    pub fn neighbors8(self) -> [Self; 8] {
+        Direction::ALL.map(|d| self.step(d))
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+/// A compass direction on a grid where up is north
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// The orthogonal directions, clockwise starting from up
+    pub const ORTHOGONAL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// The diagonal directions, clockwise starting from up right
+    pub const DIAGONAL: [Self; 4] = [Self::UpRight, Self::DownRight, Self::DownLeft, Self::UpLeft];
+
+    /// All directions, clockwise starting from up
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// Rotate clockwise in steps of 45 degrees
+    fn rotate(self, steps: usize) -> Self {
+        Self::ALL[(self as usize + steps) % Self::ALL.len()]
+    }
+
+    /// Turn 90 degrees clockwise
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Turn 90 degrees counter clockwise
+    #[allow(dead_code)]
+    pub fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// Turn 180 degrees
+    #[allow(dead_code)]
+    pub fn turn_around(self) -> Self {
+        self.rotate(4)
+    }
+
+    /// Return true if the direction is not diagonal
+    #[allow(dead_code)]
+    pub fn is_orthogonal(self) -> bool {
+        self as usize % 2 == 0
+    }
+
+    /// The offset of a single step in this direction
+    pub fn offset(self) -> Point {
+        let (x, y) = match self {
+            Self::Up => (0, -1),
+            Self::UpRight => (1, -1),
+            Self::Right => (1, 0),
+            Self::DownRight => (1, 1),
+            Self::Down => (0, 1),
+            Self::DownLeft => (-1, 1),
+            Self::Left => (-1, 0),
+            Self::UpLeft => (-1, -1),
+        };
+        Point::new(x, y)
+    }
+}
+
+/// Parse an orthogonal direction from an arrow (`^>v<`), a compass point (`NESW`) or a letter
+/// (`UDLR`)
+impl TryFrom<char> for Direction {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self> {
+        match c {
+            '^' | 'N' | 'U' => Ok(Self::Up),
+            '>' | 'E' | 'R' => Ok(Self::Right),
+            'v' | 'S' | 'D' => Ok(Self::Down),
+            '<' | 'W' | 'L' => Ok(Self::Left),
+            _ => Err(anyhow!("Invalid direction {:?}", c)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(3, -2);
+        let b = Point::new(-1, 5);
+        assert_eq!(a + b, Point::new(2, 3));
+        assert_eq!(a - b, Point::new(4, -7));
+        assert_eq!(a * 3, Point::new(9, -6));
+        assert_eq!(-a, Point::new(-3, 2));
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(
+            Point::new(-1, 7).rem_euclid(Point::new(5, 5)),
+            Point::new(4, 2)
+        );
+    }
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::UpLeft.turn_around(), Direction::DownRight);
+        assert!(Direction::DIAGONAL.iter().all(|d| !d.is_orthogonal()));
+        for d in Direction::ALL {
+            assert_eq!(d.offset() + d.turn_around().offset(), Point::ORIGIN);
+        }
+    }
+
+    #[test]
+    fn test_parse_direction() {
+        for (chars, dir) in ["^NU", ">ER", "vSD", "<WL"]
+            .into_iter()
+            .zip(Direction::ORTHOGONAL)
+        {
+            for c in chars.chars() {
+                assert_eq!(Direction::try_from(c).unwrap(), dir);
+            }
+        }
+        assert!(Direction::try_from('x').is_err());
+    }
+}
@@ -0,0 +1,345 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Every node reached by a search together with its cost from the start and the node it was first
+/// reached from
+#[derive(Debug, Clone)]
+pub struct Reached<N, C> {
+    #[allow(dead_code)]
+    start: N,
+    costs: HashMap<N, C>,
+    predecessors: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Reached<N, C> {
+    fn new(start: N, zero: C) -> Self {
+        Self {
+            costs: HashMap::from([(start.clone(), zero)]),
+            start,
+            predecessors: HashMap::new(),
+        }
+    }
+
+    /// Return the cost of reaching the given node, or `None` if it is unreachable
+    #[allow(dead_code)]
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    /// Iterate over every reached node, including the start, and its cost
+    pub fn iter(&self) -> impl Iterator<Item = (&N, C)> {
+        self.costs.iter().map(|(node, &cost)| (node, cost))
+    }
+
+    /// Return the number of reached nodes, including the start
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    /// Return the path from the start to the given node, including both ends
+    #[allow(dead_code)]
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.costs.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while *path.last().unwrap() != self.start {
+            path.push(self.predecessors[path.last().unwrap()].clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth first search where every step costs one
+pub fn bfs<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> Reached<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut reached = Reached::new(start.clone(), 0);
+    let mut to_visit = VecDeque::from([(start, 0)]);
+    while let Some((node, distance)) = to_visit.pop_front() {
+        for next in successors(&node) {
+            if reached.costs.contains_key(&next) {
+                continue;
+            }
+            reached.costs.insert(next.clone(), distance + 1);
+            reached.predecessors.insert(next.clone(), node.clone());
+            to_visit.push_back((next, distance + 1));
+        }
+    }
+    reached
+}
+
+/// Depth first search returning every reachable node in the order they were visited
+#[allow(dead_code)]
+pub fn dfs<N, I>(start: N, mut successors: impl FnMut(&N) -> I) -> Vec<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    let mut to_visit = vec![start];
+    while let Some(node) = to_visit.pop() {
+        if !visited.insert(node.clone()) {
+            continue;
+        }
+        order.push(node.clone());
+
+        // Reverse so the first successor is visited first
+        let mut next: Vec<_> = successors(&node).into_iter().collect();
+        next.reverse();
+        to_visit.extend(next.into_iter().filter(|n| !visited.contains(n)));
+    }
+    order
+}
+
+/// A node waiting in the priority queue, ordered so the cheapest is popped first
+#[allow(dead_code)]
+struct Queued<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+/// Find the cheapest cost to every reachable node. The successor function returns each neighbor
+/// with the cost of stepping to it, which must not be negative
+#[allow(dead_code)]
+pub fn dijkstra<N, C, I>(start: N, mut successors: impl FnMut(&N) -> I) -> Reached<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut reached = Reached::new(start.clone(), C::default());
+    let mut queue = BinaryHeap::from([Queued {
+        priority: C::default(),
+        cost: C::default(),
+        node: start,
+    }]);
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if reached.costs.get(&node).is_some_and(|&best| best < cost) {
+            continue;
+        }
+
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            if reached
+                .costs
+                .get(&next)
+                .is_some_and(|&best| best <= next_cost)
+            {
+                continue;
+            }
+            reached.costs.insert(next.clone(), next_cost);
+            reached.predecessors.insert(next.clone(), node.clone());
+            queue.push(Queued {
+                priority: next_cost,
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+    reached
+}
+
+/// Find the cheapest path from the start to a goal, guided by a heuristic that must never
+/// overestimate the remaining cost. Returns the path, including both ends, and its cost
+#[allow(dead_code)]
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut reached = Reached::new(start.clone(), C::default());
+    let mut queue = BinaryHeap::from([Queued {
+        priority: heuristic(&start),
+        cost: C::default(),
+        node: start,
+    }]);
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        if is_goal(&node) {
+            return Some((reached.path_to(&node)?, cost));
+        }
+        if reached.costs.get(&node).is_some_and(|&best| best < cost) {
+            continue;
+        }
+
+        for (next, step) in successors(&node) {
+            let next_cost = cost + step;
+            if reached
+                .costs
+                .get(&next)
+                .is_some_and(|&best| best <= next_cost)
+            {
+                continue;
+            }
+            reached.costs.insert(next.clone(), next_cost);
+            reached.predecessors.insert(next.clone(), node.clone());
+            queue.push(Queued {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                node: next,
+            });
+        }
+    }
+    None
+}
+
+/// Count the distinct paths from the start to any goal. The graph must be acyclic since every
+/// path is counted, not just the shortest
+pub fn count_paths<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> usize
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    fn count<N, I>(
+        node: N,
+        successors: &mut impl FnMut(&N) -> I,
+        is_goal: &mut impl FnMut(&N) -> bool,
+        counts: &mut HashMap<N, usize>,
+    ) -> usize
+    where
+        N: Clone + Eq + Hash,
+        I: IntoIterator<Item = N>,
+    {
+        if let Some(&n) = counts.get(&node) {
+            return n;
+        }
+
+        let n = if is_goal(&node) {
+            1
+        } else {
+            let next: Vec<_> = successors(&node).into_iter().collect();
+            next.into_iter()
+                .map(|next| count(next, successors, is_goal, counts))
+                .sum()
+        };
+        counts.insert(node, n);
+        n
+    }
+
+    count(start, &mut successors, &mut is_goal, &mut HashMap::new())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::utils::grid::Grid;
+    use crate::utils::point::Point;
+
+    const MAZE: &str = "S.#\n#..\n..E\n";
+
+    fn open_neighbors(maze: &Grid<char>, p: Point) -> Vec<Point> {
+        maze.neighbors4(p).filter(|&n| maze[n] != '#').collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = Grid::parse(MAZE, Ok).unwrap();
+        let start = maze.find(&'S').unwrap();
+        let end = maze.find(&'E').unwrap();
+
+        let reached = bfs(start, |&p| open_neighbors(&maze, p));
+        assert_eq!(reached.len(), 7);
+        assert_eq!(reached.cost(&end), Some(4));
+        assert_eq!(reached.cost(&Point::new(2, 0)), None);
+
+        let path = reached.path_to(&end).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!((path[0], path[4]), (start, end));
+        assert!(path.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+    }
+
+    #[test]
+    fn test_dfs() {
+        let graph = HashMap::from([(1, vec![2, 3]), (2, vec![4]), (3, vec![4]), (4, vec![])]);
+        assert_eq!(dfs(1, |n| graph[n].clone()), [1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // The direct edge is more expensive than the detour
+        let graph = HashMap::from([
+            ('a', vec![('b', 1), ('c', 5)]),
+            ('b', vec![('c', 1)]),
+            ('c', vec![('d', 2)]),
+            ('d', vec![]),
+        ]);
+        let reached = dijkstra('a', |n| graph[n].clone());
+        assert_eq!(reached.cost(&'c'), Some(2));
+        assert_eq!(reached.cost(&'d'), Some(4));
+        assert_eq!(reached.path_to(&'d').unwrap(), ['a', 'b', 'c', 'd']);
+    }
+
+    #[test]
+    fn test_astar() {
+        let maze = Grid::parse(MAZE, Ok).unwrap();
+        let start = maze.find(&'S').unwrap();
+        let end = maze.find(&'E').unwrap();
+
+        let (path, cost) = astar(
+            start,
+            |&p| open_neighbors(&maze, p).into_iter().map(|n| (n, 1)),
+            |p| p.manhattan(end),
+            |&p| p == end,
+        )
+        .unwrap();
+        assert_eq!(cost, 4);
+        assert_eq!(path.len(), 5);
+
+        let blocked = astar(start, |_| Vec::new(), |_| 0, |&p| p == end);
+        assert_eq!(blocked, None);
+    }
+
+    #[test]
+    fn test_count_paths() {
+        // Two ways to reach 4 and then two ways to reach 6 from there
+        let graph = HashMap::from([
+            (1, vec![2, 3]),
+            (2, vec![4]),
+            (3, vec![4]),
+            (4, vec![5, 6]),
+            (5, vec![6]),
+            (6, vec![]),
+        ]);
+        assert_eq!(count_paths(1, |n| graph[n].clone(), |&n| n == 6), 4);
+        assert_eq!(count_paths(1, |n| graph[n].clone(), |&n| n == 7), 0);
+    }
+}
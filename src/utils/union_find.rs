@@ -0,0 +1,170 @@
+use super::grid::Grid;
+
+/// A disjoint set forest over the elements `0..n`
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    num_components: usize,
+}
+
+impl UnionFind {
+    /// Create `n` elements that are each in their own component
+    pub fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).collect(),
+            ranks: vec![0; n],
+            sizes: vec![1; n],
+            num_components: n,
+        }
+    }
+
+    /// Return the number of elements
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Return the number of disjoint components
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    /// Return the representative of the component that contains the element
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Point everything on the way directly at the root to keep later lookups short
+        let mut x = x;
+        while self.parents[x] != root {
+            let next = self.parents[x];
+            self.parents[x] = root;
+            x = next;
+        }
+        root
+    }
+
+    /// Merge the components of the two elements. Returns false if they were already connected
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        // Attach the shallower tree below the deeper one
+        let (root, child) = if self.ranks[a] < self.ranks[b] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+        if self.ranks[root] == self.ranks[child] {
+            self.ranks[root] += 1;
+        }
+        self.num_components -= 1;
+        true
+    }
+
+    /// Return true if the two elements are in the same component
+    #[allow(dead_code)]
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Return the number of elements in the component that contains the element
+    #[allow(dead_code)]
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// Return the elements of every component, ordered by their smallest element
+    #[allow(dead_code)]
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let labels = self.labels();
+        let mut components = vec![Vec::new(); self.num_components];
+        for (x, label) in labels.into_iter().enumerate() {
+            components[label].push(x);
+        }
+        components
+    }
+
+    /// Return a dense label in `0..num_components` for every element, numbered in order of each
+    /// component's smallest element
+    pub fn labels(&mut self) -> Vec<usize> {
+        let mut label_of_root = vec![usize::MAX; self.len()];
+        let mut next_label = 0;
+        (0..self.len())
+            .map(|x| {
+                let root = self.find(x);
+                if label_of_root[root] == usize::MAX {
+                    label_of_root[root] = next_label;
+                    next_label += 1;
+                }
+                label_of_root[root]
+            })
+            .collect()
+    }
+}
+
+/// Label the orthogonally connected regions of equal cells. Returns a grid with the label of every
+/// cell and the number of regions, where labels are numbered in reading order
+pub fn label_components<T: PartialEq>(grid: &Grid<T>) -> (Grid<usize>, usize) {
+    let width = grid.width();
+    let cells: Vec<&T> = grid.iter().map(|(_, cell)| cell).collect();
+
+    // Joining every cell with its right and lower neighbor covers every edge once
+    let mut sets = UnionFind::new(cells.len());
+    for (i, cell) in cells.iter().enumerate() {
+        if (i + 1) % width != 0 && cells[i + 1] == *cell {
+            sets.union(i, i + 1);
+        }
+        if i + width < cells.len() && cells[i + width] == *cell {
+            sets.union(i, i + width);
+        }
+    }
+
+    let mut labels = sets.labels().into_iter();
+    let labelled = grid.map(|_| labels.next().unwrap());
+    (labelled, sets.num_components())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.num_components(), 6);
+
+        assert!(sets.union(0, 3));
+        assert!(sets.union(3, 5));
+        assert!(sets.union(1, 2));
+        assert!(!sets.union(5, 0));
+
+        assert!(sets.connected(0, 5));
+        assert!(!sets.connected(0, 1));
+        assert_eq!(sets.size(5), 3);
+        assert_eq!(sets.num_components(), 3);
+        assert_eq!(sets.components(), [vec![0, 3, 5], vec![1, 2], vec![4]]);
+    }
+
+    #[test]
+    fn test_label_components() {
+        // The two A regions touch only diagonally so they are separate
+        let grid = Grid::parse("AAB\nBBA\nBAA\n", Ok).unwrap();
+        let (labels, count) = label_components(&grid);
+        assert_eq!(count, 4);
+        assert_eq!(labels.to_string(), "001\n223\n233\n");
+    }
+}
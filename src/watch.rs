@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::answer::Answer;
+use crate::report::pad_newlines;
+
+/// Clears the terminal and moves the cursor to the top left corner
+pub const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Detects changes to files by polling their modification times. Files that are added to or
+/// removed from a watched directory count as changes too
+pub struct Watcher {
+    files: Vec<PathBuf>,
+    dir: Option<PathBuf>,
+    last: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Watcher {
+    pub fn new(files: Vec<PathBuf>, dir: Option<PathBuf>) -> Self {
+        let mut watcher = Self {
+            files,
+            dir,
+            last: Vec::new(),
+        };
+        watcher.last = watcher.snapshot();
+        watcher
+    }
+
+    /// Return the files in the watched directory, ordered by name
+    pub fn dir_files(&self) -> Vec<PathBuf> {
+        let Some(dir) = &self.dir else {
+            return Vec::new();
+        };
+        let mut files: Vec<_> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn snapshot(&self) -> Vec<(PathBuf, Option<SystemTime>)> {
+        self.files
+            .iter()
+            .cloned()
+            .chain(self.dir_files())
+            .map(|path| {
+                let modified = modified(&path);
+                (path, modified)
+            })
+            .collect()
+    }
+
+    /// Block until a watched file changes, checking once every interval
+    pub fn wait(&mut self, interval: Duration) {
+        loop {
+            thread::sleep(interval);
+            let snapshot = self.snapshot();
+            if snapshot != self.last {
+                self.last = snapshot;
+                return;
+            }
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Describe how the answers changed since the previous run, with one line per part
+pub fn diff(
+    previous: Option<&(Answer, Option<Answer>)>,
+    current: &(Answer, Option<Answer>),
+) -> Vec<String> {
+    let Some(previous) = previous else {
+        return Vec::new();
+    };
+
+    let describe =
+        |part: char, before: Option<&Answer>, after: Option<&Answer>| match (before, after) {
+            (Some(before), Some(after)) if before == after => format!("{}: unchanged", part),
+            (Some(before), Some(after)) => format!(
+                "{}: {} (was {})",
+                part,
+                pad_newlines(&after.to_string()),
+                pad_newlines(&before.to_string())
+            ),
+            (None, Some(after)) => format!("{}: {} (new)", part, pad_newlines(&after.to_string())),
+            (Some(before), None) => {
+                format!("{}: gone (was {})", part, pad_newlines(&before.to_string()))
+            }
+            (None, None) => format!("{}: unchanged", part),
+        };
+    vec![
+        describe('A', Some(&previous.0), Some(&current.0)),
+        describe('B', previous.1.as_ref(), current.1.as_ref()),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let answers = |a: usize, b: Option<usize>| (Answer::from(a), b.map(Answer::from));
+        assert!(diff(None, &answers(1, None)).is_empty());
+        assert_eq!(
+            diff(Some(&answers(1, None)), &answers(1, Some(2))),
+            ["A: unchanged", "B: 2 (new)"]
+        );
+        assert_eq!(
+            diff(Some(&answers(1, Some(2))), &answers(3, None)),
+            ["A: 3 (was 1)", "B: gone (was 2)"]
+        );
+    }
+
+    #[test]
+    fn test_watcher() {
+        let path = std::env::temp_dir().join(format!("aoc-test-watch-{}.txt", std::process::id()));
+        fs::write(&path, "1").unwrap();
+        let mut watcher = Watcher::new(vec![path.clone()], None);
+
+        // Removing the file is a change that wait returns on
+        fs::remove_file(&path).unwrap();
+        watcher.wait(Duration::from_millis(1));
+        assert_eq!(watcher.last, [(path, None)]);
+    }
+}